@@ -8,6 +8,12 @@ fn main() {
     if args.len() == 1 {
         // Interactive REPL.
         repl();
+    } else if args.len() == 3 && args[1] == "--ast" {
+        // Print the parsed program without evaluating it.
+        match dump_ast(&args[2]) {
+            Ok(ast) => println!("{}", ast),
+            Err(error) => println!("{}", error),
+        }
     } else {
         // Interpret a file.
         let path = &args[1];
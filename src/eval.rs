@@ -1,6 +1,10 @@
-use crate::types::Expr;
-use std::collections::HashMap;
+use crate::types::{Expr, MAX_RENDER_DEPTH};
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::rc::Rc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 #[derive(Debug, PartialEq)]
 pub enum EvalResult {
@@ -9,9 +13,83 @@ pub enum EvalResult {
     Unit,
 }
 
+/// Control flow in progress, raised alongside an error that explains where it is not allowed
+#[derive(Debug, PartialEq)]
+pub enum Signal {
+    /// Leave the enclosing function with the given value
+    Return(Rc<Expr>),
+    /// Leave the enclosing loop
+    Break,
+    /// Skip to the next iteration of the enclosing loop
+    Continue,
+}
+
+/// Parameter names and body of a function, or no parameters and the value of a variable
+pub type Binding = (Vec<String>, Rc<Expr>);
+
+/// A single scope mapping names to their parameters and bodies
+pub type Context = HashMap<String, Binding>;
+
+/// Per-arity definitions of the overloaded functions in a single scope
+pub type Overloads = HashMap<String, Vec<Binding>>;
+
+/// Results of a memoized function keyed by the canonical form of its arguments, since Expr has no Hash
+pub type MemoTable = HashMap<String, Rc<Expr>>;
+
+/// Signature of a function implemented in Rust
+pub type NativeFnBody = Box<dyn Fn(&[Rc<Expr>]) -> EvalResult>;
+
+/// A function implemented in Rust, called with already evaluated arguments
+pub struct NativeFn(NativeFnBody);
+
+impl fmt::Debug for NativeFn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<native-fn>")
+    }
+}
+
 #[derive(Debug)]
 pub struct Environment {
-    pub contexts: Vec<HashMap<String, (Vec<String>, Rc<Expr>)>>,
+    pub contexts: Vec<Context>,
+    /// Overloaded functions, one table per context
+    pub overloads: Vec<Overloads>,
+    /// Names bound with defconst, one set per context
+    pub constants: Vec<HashSet<String>>,
+    /// Caches of the functions defined with defmemo, one table per context
+    pub memos: Vec<HashMap<String, MemoTable>>,
+    /// Functions registered by the embedding Rust program
+    pub natives: HashMap<String, NativeFn>,
+    /// Names of the functions currently being applied, outermost first
+    pub call_stack: Vec<String>,
+    /// Call stack captured when the first error was produced
+    pub error_trace: Option<Vec<String>>,
+    /// Control flow travelling up to the form that handles it
+    pub signal: Option<Signal>,
+    /// Non-fatal warnings collected during evaluation, oldest first
+    pub warnings: Vec<String>,
+    /// Warn on stderr when a variable shadows one in an outer context
+    pub warn_shadowing: bool,
+    /// Treat symbols case-insensitively, set through set_fold_case
+    pub fold_case: bool,
+    /// Time after which evaluation fails, set by with-timeout
+    pub deadline: Option<Instant>,
+    /// Nesting depth beyond which print shows ... instead of the structure
+    pub max_print_depth: usize,
+    /// Treat the number 0 as false in conditions, like C does
+    pub zero_is_falsy: bool,
+    /// State of the random number generator used by shuffle, set by seed-rng
+    pub rng_state: u64,
+}
+
+impl Default for Environment {
+    /// Create a default environment
+    fn default() -> Environment {
+        let mut env = Environment::empty();
+        env.push_context();
+        let _ = env.add_var("False", Expr::list(&[]));
+        let _ = env.add_var("True", Expr::list(&[Expr::int(1)]));
+        env
+    }
 }
 
 impl Environment {
@@ -19,6 +97,22 @@ impl Environment {
     pub fn empty() -> Environment {
         Environment {
             contexts: Vec::new(),
+            overloads: Vec::new(),
+            constants: Vec::new(),
+            memos: Vec::new(),
+            natives: HashMap::new(),
+            call_stack: Vec::new(),
+            error_trace: None,
+            signal: None,
+            warnings: Vec::new(),
+            warn_shadowing: false,
+            fold_case: false,
+            deadline: None,
+            max_print_depth: MAX_RENDER_DEPTH,
+            zero_is_falsy: false,
+            rng_state: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(1, |time| time.as_nanos() as u64),
         }
     }
 
@@ -32,28 +126,20 @@ impl Environment {
         env
     }
 
-    /// Create a default environment
-    pub fn default() -> Environment {
-        let mut env = Environment::empty();
-        env.push_context();
-        let _ = env.add_var("False", Expr::list(&[]));
-        let _ = env.add_var("True", Expr::list(&[Expr::fnum(1.0)]));
-        env
-    }
-
     /// Look up the given symbol in the environment
     pub fn lookup(&self, symbol: &str) -> Option<(Vec<String>, Rc<Expr>)> {
+        let symbol = &*self.key(symbol);
         self.contexts
             .iter()
             .rev()
             .find(|context| context.contains_key(symbol))
-            .map(|context| context.get(symbol))
-            .flatten()
+            .and_then(|context| context.get(symbol))
             .cloned()
     }
 
     /// Check whether the given symbol exists in the environment
     pub fn contains_key(&self, symbol: &str) -> bool {
+        let symbol = &*self.key(symbol);
         self.contexts
             .iter()
             .rev()
@@ -64,47 +150,311 @@ impl Environment {
     /// Push a new context on the stack
     pub fn push_context(&mut self) {
         self.contexts.push(HashMap::new());
+        self.overloads.push(HashMap::new());
+        self.constants.push(HashSet::new());
+        self.memos.push(HashMap::new());
     }
 
     /// Pop the last context from the stack
     pub fn pop_context(&mut self) {
         self.contexts.pop();
+        self.overloads.pop();
+        self.constants.pop();
+        self.memos.pop();
     }
 
     /// Add a variable definition to the environment
     pub fn add_var(&mut self, var: &str, val: Rc<Expr>) -> Result<(), String> {
+        if let Some(warning) = self.shadow_warning(var) {
+            self.warn(warning);
+        }
+        self.insert_var(var, val)
+    }
+
+    /// Add a variable definition to the innermost context without any shadowing warning
+    fn insert_var(&mut self, var: &str, val: Rc<Expr>) -> Result<(), String> {
+        let var = self.key(var).into_owned();
+        self.check_not_constant(&var)?;
+        if let Some(overloads) = self.overloads.last_mut() {
+            overloads.remove(&var);
+        }
+        if let Some(memos) = self.memos.last_mut() {
+            memos.remove(&var);
+        }
         self.contexts.last_mut().map_or_else(
             || Err("Enviroment has no context!".into()),
             |context| {
-                context.insert(var.to_string(), (Vec::new(), val.clone()));
+                context.insert(var, (Vec::new(), val.clone()));
                 Ok(())
             },
         )
     }
 
+    /// Add a constant to the environment, which cannot be redefined in the same context
+    pub fn add_const(&mut self, var: &str, val: Rc<Expr>) -> Result<(), String> {
+        self.add_var(var, val)?;
+        let var = self.key(var).into_owned();
+        self.constants.last_mut().map_or_else(
+            || Err("Enviroment has no context!".into()),
+            |constants| {
+                constants.insert(var);
+                Ok(())
+            },
+        )
+    }
+
+    /// Check whether the given symbol is bound to a constant
+    pub fn is_constant(&self, symbol: &str) -> bool {
+        let symbol = &*self.key(symbol);
+        self.contexts
+            .iter()
+            .rposition(|context| context.contains_key(symbol))
+            .and_then(|index| self.constants.get(index))
+            .is_some_and(|constants| constants.contains(symbol))
+    }
+
+    /// Fail if the given name is a constant of the current context
+    fn check_not_constant(&self, name: &str) -> Result<(), String> {
+        match self.constants.last() {
+            Some(constants) if constants.contains(name) => {
+                Err(format!("Cannot redefine constant {}!", name))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Update a variable in the innermost context that binds it
+    pub fn set_var(&mut self, var: &str, val: Rc<Expr>) -> Result<(), String> {
+        if self.is_constant(var) {
+            return Err(format!("Cannot redefine constant {}!", var));
+        }
+        let key = self.key(var).into_owned();
+        let index = self
+            .contexts
+            .iter()
+            .rposition(|context| context.contains_key(&key))
+            .ok_or_else(|| format!("Cannot set unbound variable {}!", var))?;
+        if let Some(overloads) = self.overloads.get_mut(index) {
+            overloads.remove(&key);
+        }
+        if let Some(memos) = self.memos.get_mut(index) {
+            memos.remove(&key);
+        }
+        self.contexts[index].insert(key, (Vec::new(), val));
+        Ok(())
+    }
+
+    /// Get the warning for binding the given variable, if it shadows an outer one
+    pub fn shadow_warning(&self, var: &str) -> Option<String> {
+        let key = &*self.key(var);
+        let outer = self.contexts.len().saturating_sub(1);
+        if self.warn_shadowing && self.contexts[..outer].iter().any(|c| c.contains_key(key)) {
+            Some(format!(
+                "Warning: {} shadows a binding in an outer context!",
+                var
+            ))
+        } else {
+            None
+        }
+    }
+
     /// Add a function definition to the environment
     pub fn add_fn(&mut self, name: &str, params: &[String], body: Rc<Expr>) -> Result<(), String> {
+        let name = self.key(name).into_owned();
+        self.check_not_constant(&name)?;
+        if let Some(overloads) = self.overloads.last_mut() {
+            overloads.remove(&name);
+        }
+        if let Some(memos) = self.memos.last_mut() {
+            memos.remove(&name);
+        }
         self.contexts.last_mut().map_or_else(
             || Err("Enviroment has no context!".into()),
             |context| {
                 let params = params.iter().map(|s| s.to_string()).collect();
-                context.insert(name.to_string(), (params, body));
+                context.insert(name, (params, body));
+                Ok(())
+            },
+        )
+    }
+
+    /// Turn case-insensitive symbols on or off, folding existing bindings when turned on
+    pub fn set_fold_case(&mut self, fold_case: bool) {
+        self.fold_case = fold_case;
+        if fold_case {
+            for context in self.contexts.iter_mut() {
+                *context = context
+                    .drain()
+                    .map(|(name, binding)| (name.to_lowercase(), binding))
+                    .collect();
+            }
+            for overloads in self.overloads.iter_mut() {
+                *overloads = overloads
+                    .drain()
+                    .map(|(name, clauses)| (name.to_lowercase(), clauses))
+                    .collect();
+            }
+            for constants in self.constants.iter_mut() {
+                *constants = constants.drain().map(|name| name.to_lowercase()).collect();
+            }
+            for memos in self.memos.iter_mut() {
+                *memos = memos
+                    .drain()
+                    .map(|(name, table)| (name.to_lowercase(), table))
+                    .collect();
+            }
+        }
+    }
+
+    /// Get the key a symbol is stored under
+    fn key<'a>(&self, symbol: &'a str) -> Cow<'a, str> {
+        if self.fold_case {
+            Cow::Owned(symbol.to_lowercase())
+        } else {
+            Cow::Borrowed(symbol)
+        }
+    }
+
+    /// Add a function with one definition per number of arguments to the environment
+    pub fn add_overloaded_fn(&mut self, name: &str, clauses: &[Binding]) -> Result<(), String> {
+        let (params, body) = clauses
+            .first()
+            .ok_or_else(|| "Overloaded function must have at least one definition!".to_string())?;
+        self.add_fn(name, params, body.clone())?;
+        let name = self.key(name).into_owned();
+        self.overloads.last_mut().map_or_else(
+            || Err("Enviroment has no context!".into()),
+            |overloads| {
+                overloads.insert(name, clauses.to_vec());
+                Ok(())
+            },
+        )
+    }
+
+    /// Look up the definition of an overloaded function taking the given number of arguments
+    pub fn lookup_overload(&self, symbol: &str, arity: usize) -> Option<Result<Binding, String>> {
+        let symbol = &*self.key(symbol);
+        let index = self
+            .contexts
+            .iter()
+            .rposition(|context| context.contains_key(symbol))?;
+        let clauses = self.overloads.get(index)?.get(symbol)?;
+        Some(
+            clauses
+                .iter()
+                .find(|(params, _)| params.len() == arity)
+                .cloned()
+                .ok_or_else(|| format!("No definition of {} takes {} arguments!", symbol, arity)),
+        )
+    }
+
+    /// Add a function whose results are cached by its arguments to the environment
+    pub fn add_memoized_fn(
+        &mut self,
+        name: &str,
+        params: &[String],
+        body: Rc<Expr>,
+    ) -> Result<(), String> {
+        self.add_fn(name, params, body)?;
+        let name = self.key(name).into_owned();
+        self.memos.last_mut().map_or_else(
+            || Err("Enviroment has no context!".into()),
+            |memos| {
+                memos.insert(name, HashMap::new());
                 Ok(())
             },
         )
     }
 
+    /// Get the cache of the given function, if it was defined with defmemo
+    pub fn memo_table(&mut self, symbol: &str) -> Option<&mut MemoTable> {
+        let symbol = &*self.key(symbol);
+        let index = self
+            .contexts
+            .iter()
+            .rposition(|context| context.contains_key(symbol))?;
+        self.memos.get_mut(index)?.get_mut(symbol)
+    }
+
+    /// Register a native Rust function callable from Risp code
+    pub fn register_native(&mut self, name: &str, f: NativeFnBody) -> Result<(), String> {
+        reserved_words(name)?;
+        self.natives.insert(name.to_string(), NativeFn(f));
+        Ok(())
+    }
+
     /// Get the number of all contexts
     pub fn num_contexts(&self) -> usize {
         self.contexts.len()
     }
+
+    /// Get all bound and builtin names starting with the given prefix
+    pub fn completions(&self, prefix: &str) -> Vec<String> {
+        let mut names = self
+            .contexts
+            .iter()
+            .flat_map(|context| context.keys().cloned())
+            .chain(self.natives.keys().cloned())
+            .chain(RESERVED_WORDS.iter().map(|s| s.to_string()))
+            .filter(|name| name.starts_with(prefix))
+            .collect::<Vec<String>>();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Seed the random number generator, so that shuffle is reproducible
+    pub fn seed_rng(&mut self, seed: u64) {
+        self.rng_state = seed;
+    }
+
+    /// Get the next pseudo-random number, using xorshift64*
+    pub fn next_random(&mut self) -> u64 {
+        let mut x = if self.rng_state == 0 {
+            0x9e37_79b9_7f4a_7c15
+        } else {
+            self.rng_state
+        };
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.rng_state = x;
+        x.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// Take the call stack captured when the last error was produced
+    pub fn take_error_trace(&mut self) -> Option<Vec<String>> {
+        self.error_trace.take()
+    }
+
+    /// Record a warning without interrupting evaluation
+    pub fn warn(&mut self, warning: String) {
+        self.warnings.push(warning);
+    }
+
+    /// Take the warnings collected since they were last taken
+    pub fn take_warnings(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.warnings)
+    }
 }
 
 /// Evaluate the given expression
 pub fn eval(expr: Rc<Expr>, env: &mut Environment) -> EvalResult {
+    if env
+        .deadline
+        .is_some_and(|deadline| Instant::now() >= deadline)
+    {
+        return EvalResult::Err("Evaluation timed out!".into());
+    }
+
     match &*expr {
         Expr::Symbol(s) => evaluate_symbol(expr.clone(), s, &[], env),
-        Expr::FNum(_) => EvalResult::Expr(expr.clone()),
+        Expr::Keyword(_)
+        | Expr::Str(_)
+        | Expr::Int(_)
+        | Expr::FNum(_)
+        | Expr::Ratio(..)
+        | Expr::Lambda { .. } => EvalResult::Expr(expr.clone()),
         Expr::List(vals) => {
             if vals.is_empty() {
                 return EvalResult::Expr(Expr::list(&[]));
@@ -116,6 +466,11 @@ pub fn eval(expr: Rc<Expr>, env: &mut Environment) -> EvalResult {
                 Expr::Symbol(s) if s == "-" => do_math(&vals[1..], env, "-"),
                 Expr::Symbol(s) if s == "*" => do_math(&vals[1..], env, "*"),
                 Expr::Symbol(s) if s == "/" => do_math(&vals[1..], env, "/"),
+                Expr::Symbol(s) if s == "%" => do_math(&vals[1..], env, "%"),
+                Expr::Symbol(s) if s == "add1" => do_unary_math(&vals[1..], env, "add1"),
+                Expr::Symbol(s) if s == "sub1" => do_unary_math(&vals[1..], env, "sub1"),
+                Expr::Symbol(s) if s == "square" => do_unary_math(&vals[1..], env, "square"),
+                Expr::Symbol(s) if s == "cube" => do_unary_math(&vals[1..], env, "cube"),
 
                 Expr::Symbol(s) if s == "or" => do_boolean(&vals[1..], env, "or"),
                 Expr::Symbol(s) if s == "and" => do_boolean(&vals[1..], env, "and"),
@@ -123,19 +478,104 @@ pub fn eval(expr: Rc<Expr>, env: &mut Environment) -> EvalResult {
 
                 Expr::Symbol(s) if s == "=" => do_equality(&vals[1..], env, "="),
                 Expr::Symbol(s) if s == "!=" => do_equality(&vals[1..], env, "!="),
+                Expr::Symbol(s) if s == "<" => do_compare(&vals[1..], env, "<"),
+                Expr::Symbol(s) if s == ">" => do_compare(&vals[1..], env, ">"),
+                Expr::Symbol(s) if s == "<=" => do_compare(&vals[1..], env, "<="),
+                Expr::Symbol(s) if s == ">=" => do_compare(&vals[1..], env, ">="),
+                Expr::Symbol(s) if s == "equal?" => do_equality(&vals[1..], env, "="),
+                Expr::Symbol(s) if s == "identical?" => identical(&vals[1..], env),
                 Expr::Symbol(s) if s == "if" => if_statement(&vals[1..], env),
+                Expr::Symbol(s) if s == "select" => select(&vals[1..], env),
+                Expr::Symbol(s) if s == "cond" => cond(&vals[1..], env),
+                Expr::Symbol(s) if s == "begin" => begin(&vals[1..], env),
+                Expr::Symbol(s) if s == "while" => while_loop(&vals[1..], env),
+                Expr::Symbol(s) if s == "return" => return_from_fn(&vals[1..], env),
+                Expr::Symbol(s) if s == "break" => loop_signal(&vals[1..], env, s),
+                Expr::Symbol(s) if s == "continue" => loop_signal(&vals[1..], env, s),
 
                 Expr::Symbol(s) if s == "let" => add_var_to_env(&vals[1..], env),
                 Expr::Symbol(s) if s == "fn" => add_fun_to_env(&vals[1..], env),
+                Expr::Symbol(s) if s == "defmemo" => add_memo_fun_to_env(&vals[1..], env),
+                Expr::Symbol(s) if s == "lambda" => lambda(&vals[1..]),
+                Expr::Symbol(s) if s == "defconst" => add_const_to_env(&vals[1..], env),
+                Expr::Symbol(s) if s == "set!" => set_var_in_env(&vals[1..], env),
                 Expr::Symbol(s) if s == "print" => print(&vals[1..], env),
+                Expr::Symbol(s) if s == "symbol-append" => symbol_append(&vals[1..], env),
+                Expr::Symbol(s) if s == "quote" => quote(&vals[1..]),
+                Expr::Symbol(s) if s == "quasiquote" => quasiquote(&vals[1..], env),
+                Expr::Symbol(s) if s == "current-time-ms" => current_time_ms(&vals[1..]),
+                Expr::Symbol(s) if s == "elapsed" => elapsed(&vals[1..], env),
+                Expr::Symbol(s) if s == "with-timeout" => with_timeout(&vals[1..], env),
+                Expr::Symbol(s) if s == "inspect" => inspect(&vals[1..], env),
+                Expr::Symbol(s) if s == "spy" => spy(&vals[1..], env),
+                Expr::Symbol(s) if s == "dbg" => dbg(&vals[1..], env),
+                Expr::Symbol(s) if s == "compare" => compare(&vals[1..], env),
+                Expr::Symbol(s) if s == "diff" => diff(&vals[1..], env),
+                Expr::Symbol(s) if s == "list" => list(&vals[1..], env),
+                Expr::Symbol(s) if s == "concat" => concat(&vals[1..], env),
+                Expr::Symbol(s) if s == "map" => map(&vals[1..], env),
+                Expr::Symbol(s) if s == "scan" => scan(&vals[1..], env),
+                Expr::Symbol(s) if s == "flat-map" => flat_map(&vals[1..], env),
+                Expr::Symbol(s) if s == "unfold" => unfold(&vals[1..], env),
+                Expr::Symbol(s) if s == "any-of" => combine_predicates(&vals[1..], env, "any-of"),
+                Expr::Symbol(s) if s == "all-of" => combine_predicates(&vals[1..], env, "all-of"),
+                Expr::Symbol(s) if s == "partition-by" => partition_by(&vals[1..], env),
+                Expr::Symbol(s) if s == "chunk-while" => chunk_while(&vals[1..], env),
+                Expr::Symbol(s) if s == "tree-map" => tree_map(&vals[1..], env),
+                Expr::Symbol(s) if s == "iterate" => iterate(&vals[1..], env),
+                Expr::Symbol(s) if s == "string-lines" => split_string(&vals[1..], env, s),
+                Expr::Symbol(s) if s == "string-words" => split_string(&vals[1..], env, s),
+                Expr::Symbol(s) if s == "string-repeat" => string_repeat(&vals[1..], env),
+                Expr::Symbol(s) if s == "string-reverse" => string_reverse(&vals[1..], env),
+                Expr::Symbol(s) if s == "string-starts-with?" => string_affix(&vals[1..], env, s),
+                Expr::Symbol(s) if s == "string-ends-with?" => string_affix(&vals[1..], env, s),
+                Expr::Symbol(s) if s == "parse-int" => parse_int(&vals[1..], env),
+                Expr::Symbol(s) if s == "parse-float" => parse_float(&vals[1..], env),
+                Expr::Symbol(s) if s == "car" => car(&vals[1..], env),
+                Expr::Symbol(s) if s == "cdr" => cdr(&vals[1..], env),
+                Expr::Symbol(s) if s == "cons" => cons(&vals[1..], env),
+                Expr::Symbol(s) if s == "butlast" => butlast(&vals[1..], env),
+                Expr::Symbol(s) if s == "last-n" => last_n(&vals[1..], env),
+                Expr::Symbol(s) if s == "split-at" => split_at(&vals[1..], env),
+                Expr::Symbol(s) if s == "interleave" => interleave(&vals[1..], env),
+                Expr::Symbol(s) if s == "interpose" => interpose(&vals[1..], env),
+                Expr::Symbol(s) if s == "rotate" => rotate(&vals[1..], env),
+                Expr::Symbol(s) if s == "shuffle" => shuffle(&vals[1..], env),
+                Expr::Symbol(s) if s == "seed-rng" => seed_rng(&vals[1..], env),
+                Expr::Symbol(s) if s == "abs" => abs(&vals[1..], env),
+                Expr::Symbol(s) if s == "sign" => sign(&vals[1..], env),
+                Expr::Symbol(s) if s == "clamp" => clamp(&vals[1..], env),
+                Expr::Symbol(s) if s == "min-list" => extremum(&vals[1..], env, "min-list"),
+                Expr::Symbol(s) if s == "max-list" => extremum(&vals[1..], env, "max-list"),
+                Expr::Symbol(s) if s == "keyword?" => is_keyword_value(&vals[1..], env),
+                Expr::Symbol(s) if s == "warnings" => warnings(&vals[1..], env),
+                Expr::Symbol(s) if s == "unquote" || s == "unquote-splicing" => {
+                    EvalResult::Err(format!("Cannot use {} outside of quasiquote!", s))
+                }
 
-                Expr::Symbol(s) if env.contains_key(&s) => {
+                Expr::Symbol(s) if env.natives.contains_key(s) => call_native(s, &vals[1..], env),
+                Expr::Symbol(s) if env.contains_key(s) => {
                     evaluate_symbol(expr.clone(), s, &vals[1..], env)
                 }
                 _ => {
-                    let result = vals
-                        .iter()
-                        .map(|e| eval(e.clone(), env))
+                    let head = eval(vals[0].clone(), env);
+                    if let EvalResult::Expr(f) = &head {
+                        match &**f {
+                            Expr::Lambda { params, body } => {
+                                return apply_lambda(params, body.clone(), &vals[1..], env);
+                            }
+                            Expr::Symbol(name)
+                                if matches!(*vals[0], Expr::List(_))
+                                    && is_function_name(name, env) =>
+                            {
+                                return call_by_name(f, &vals[1..], env);
+                            }
+                            _ => {}
+                        }
+                    }
+
+                    let result = std::iter::once(head)
+                        .chain(vals[1..].iter().map(|e| eval(e.clone(), env)))
                         .filter(|e| *e != EvalResult::Unit)
                         .map(|e| {
                             if let EvalResult::Expr(expr) = e {
@@ -159,50 +599,338 @@ fn evaluate_symbol(
     args: &[Rc<Expr>],
     env: &mut Environment,
 ) -> EvalResult {
-    env.lookup(symbol).map_or_else(
+    let binding = match env.lookup_overload(symbol, args.len()) {
+        Some(Ok(clause)) => Some(clause),
+        Some(Err(error)) => return EvalResult::Err(error),
+        None => env.lookup(symbol),
+    };
+
+    let is_call = matches!(*expr, Expr::List(_));
+    binding.map_or_else(
         || EvalResult::Expr(expr),
         |(param_names, expression)| {
             if param_names.is_empty() {
-                eval(expression.clone(), env)
-            } else {
-                if param_names.len() != args.len() {
-                    return EvalResult::Err(format!(
-                        "Provided {} arguments but expected {}!",
-                        param_names.len(),
-                        args.len()
-                    ));
+                match eval(expression.clone(), env) {
+                    EvalResult::Expr(f) if is_call => match &*f {
+                        Expr::Lambda { params, body } => {
+                            apply_lambda(params, body.clone(), args, env)
+                        }
+                        Expr::Symbol(name) if name != symbol && is_function_name(name, env) => {
+                            call_by_name(&f, args, env)
+                        }
+                        _ => EvalResult::Expr(f),
+                    },
+                    result => result,
                 }
+            } else {
+                bind_args(&param_names, args, env).map_or_else(EvalResult::Err, |arg_tuples| {
+                    call_memoized_fn(symbol, &arg_tuples, expression, env)
+                })
+            }
+        },
+    )
+}
 
-                let mapped_args = args
-                    .iter()
-                    .zip(param_names)
-                    .map(|(e, name)| match eval(e.clone(), env) {
-                        EvalResult::Err(error) => Err(error),
-                        EvalResult::Expr(expr) => Ok((name.to_string(), expr.clone())),
-                        EvalResult::Unit => {
-                            Err("Cannot pass Unit as an argument to a function!".into())
-                        }
-                    })
-                    .collect::<Result<Vec<(String, Rc<Expr>)>, String>>();
+/// Call a function held as a value, such as an operator passed as an argument
+/// The call is evaluated as if the function had been written in place, so builtins keep their fast path
+fn call_by_name(function: &Rc<Expr>, args: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    let call = std::iter::once(function.clone())
+        .chain(args.iter().cloned())
+        .collect::<Vec<Rc<Expr>>>();
+    eval(Expr::list(&call), env)
+}
 
-                env.push_context();
+/// Match arguments to the parameters of a function and evaluate them
+fn bind_args(
+    param_names: &[String],
+    args: &[Rc<Expr>],
+    env: &mut Environment,
+) -> Result<Vec<(String, Rc<Expr>)>, String> {
+    let bound_args = if param_names.iter().all(|name| is_keyword(name)) {
+        match_keyword_args(param_names, args)
+    } else if param_names.len() != args.len() {
+        Err(format!(
+            "Provided {} arguments but expected {}!",
+            param_names.len(),
+            args.len()
+        ))
+    } else {
+        Ok(param_names
+            .iter()
+            .cloned()
+            .zip(args.iter().cloned())
+            .collect())
+    };
 
-                let result = mapped_args.map_or_else(
-                    |error| EvalResult::Err(error),
-                    |arg_tuples| {
-                        arg_tuples.iter().for_each(|(name, expr)| {
-                            let _ = env.add_var(name, expr.clone());
-                        });
-                        eval(expression.clone(), env)
-                    },
-                );
+    bound_args.and_then(|pairs| {
+        pairs
+            .into_iter()
+            .map(|(name, e)| match eval(e, env) {
+                EvalResult::Err(error) => Err(error),
+                EvalResult::Expr(expr) => Ok((name, expr)),
+                EvalResult::Unit => Err("Cannot pass Unit as an argument to a function!".into()),
+            })
+            .collect::<Result<Vec<(String, Rc<Expr>)>, String>>()
+    })
+}
+
+/// Apply an anonymous function to unevaluated arguments
+fn apply_lambda(
+    params: &[String],
+    body: Rc<Expr>,
+    args: &[Rc<Expr>],
+    env: &mut Environment,
+) -> EvalResult {
+    if params.is_empty() {
+        return if args.is_empty() {
+            call_fn("lambda", &[], body, env)
+        } else {
+            EvalResult::Err(format!("Provided {} arguments but expected 0!", args.len()))
+        };
+    }
+
+    bind_args(params, args, env).map_or_else(EvalResult::Err, |arg_tuples| {
+        call_fn("lambda", &arg_tuples, body, env)
+    })
+}
+
+/// Evaluate the body of a function in a new context with its arguments bound
+fn call_fn(
+    symbol: &str,
+    args: &[(String, Rc<Expr>)],
+    body: Rc<Expr>,
+    env: &mut Environment,
+) -> EvalResult {
+    env.push_context();
+    args.iter().for_each(|(name, expr)| {
+        let _ = env.insert_var(name, expr.clone());
+    });
+    env.call_stack.push(symbol.to_string());
+    let result = match (eval(body, env), env.signal.take()) {
+        (EvalResult::Err(_), Some(Signal::Return(value))) => EvalResult::Expr(value),
+        (result, _) => result,
+    };
+    if let (EvalResult::Err(_), None) = (&result, &env.error_trace) {
+        env.error_trace = Some(env.call_stack.clone());
+    }
+    env.call_stack.pop();
+    env.pop_context();
+    result
+}
+
+/// Call a function, reusing the cached result if it was defined with defmemo
+fn call_memoized_fn(
+    symbol: &str,
+    args: &[(String, Rc<Expr>)],
+    body: Rc<Expr>,
+    env: &mut Environment,
+) -> EvalResult {
+    let key = args
+        .iter()
+        .map(|(_, value)| memo_key(value))
+        .collect::<Vec<String>>()
+        .join(" ");
+    match env.memo_table(symbol).map(|table| table.get(&key).cloned()) {
+        None => call_fn(symbol, args, body, env),
+        Some(Some(cached)) => EvalResult::Expr(cached),
+        Some(None) => {
+            let result = call_fn(symbol, args, body, env);
+            if let (EvalResult::Expr(value), Some(table)) = (&result, env.memo_table(symbol)) {
+                table.insert(key, value.clone());
+            }
+            result
+        }
+    }
+}
 
-                env.pop_context();
+/// Render an argument of a memoized function in a canonical form tagged with its type
+/// Equal values share a key, so 1 and 1.0 hit the same cached result
+fn memo_key(expr: &Expr) -> String {
+    match expr {
+        Expr::List(xs) => format!(
+            "({})",
+            xs.iter()
+                .map(|x| memo_key(x))
+                .collect::<Vec<String>>()
+                .join(" ")
+        ),
+        Expr::FNum(n) if n.fract() == 0.0 && n.abs() < i64::MAX as f64 => {
+            format!("number:{}", *n as i64)
+        }
+        e => format!("{}:{}", type_name(e), e),
+    }
+}
+
+/// Call a native function with the evaluated arguments
+fn call_native(name: &str, vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    let args = vals
+        .iter()
+        .map(|e| match eval(e.clone(), env) {
+            EvalResult::Err(error) => Err(error),
+            EvalResult::Expr(expr) => Ok(expr),
+            EvalResult::Unit => Err("Cannot pass Unit as an argument to a function!".into()),
+        })
+        .collect::<Result<Vec<Rc<Expr>>, String>>();
+
+    args.map_or_else(EvalResult::Err, |args| match env.natives.get(name) {
+        Some(NativeFn(f)) => f(&args),
+        None => EvalResult::Err(format!("Unknown native function {}!", name)),
+    })
+}
+
+/// Apply a named function to already evaluated arguments
+/// Builtins are called with each value quoted so it is not evaluated again
+fn apply_function(name: &str, args: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if let Some(NativeFn(f)) = env.natives.get(name) {
+        return f(args);
+    }
+
+    match lookup_function(name, args.len(), env) {
+        Some((params, body))
+            if !params.is_empty()
+                && params.len() == args.len()
+                && !params.iter().any(|param| is_keyword(param)) =>
+        {
+            let bound = params
+                .into_iter()
+                .zip(args.iter().cloned())
+                .collect::<Vec<(String, Rc<Expr>)>>();
+            call_fn(name, &bound, body, env)
+        }
+        _ if is_builtin(name) => {
+            let mut call = vec![Expr::symbol(name)];
+            call.extend(
+                args.iter()
+                    .map(|arg| Expr::list(&[Expr::symbol("quote"), arg.clone()])),
+            );
+            eval(Expr::list(&call), env)
+        }
+        _ => EvalResult::Err(format!(
+            "{} is not a function of {} arguments!",
+            name,
+            args.len()
+        )),
+    }
+}
+
+/// Apply a function given by name or as a lambda to already evaluated arguments
+fn apply_value(function: &Expr, args: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    match function {
+        Expr::Symbol(name) => apply_function(name, args, env),
+        Expr::Lambda { params, body } if params.len() == args.len() => {
+            let bound = params
+                .iter()
+                .cloned()
+                .zip(args.iter().cloned())
+                .collect::<Vec<(String, Rc<Expr>)>>();
+            call_fn("lambda", &bound, body.clone(), env)
+        }
+        f => EvalResult::Err(format!(
+            "{} is not a function of {} arguments!",
+            f,
+            args.len()
+        )),
+    }
+}
+
+/// Evaluate the function given to a higher-order builtin
+/// A symbol is kept as the name of a function and anything else is evaluated
+fn function_arg(expr: &Rc<Expr>, env: &mut Environment) -> Result<Rc<Expr>, String> {
+    match &**expr {
+        Expr::Symbol(_) => Ok(expr.clone()),
+        _ => eval_arg(expr, env),
+    }
+}
 
-                result
+/// Check whether a function given by name or as a lambda can take the given number of arguments
+fn accepts_args(function: &Expr, arity: usize, env: &Environment) -> bool {
+    match function {
+        Expr::Symbol(name) if env.natives.contains_key(name) => true,
+        Expr::Symbol(name) => match lookup_function(name, arity, env) {
+            Some((params, _)) => {
+                params.len() == arity && !params.iter().any(|param| is_keyword(param))
             }
+            None => is_builtin(name),
         },
-    )
+        Expr::Lambda { params, .. } => params.len() == arity,
+        _ => false,
+    }
+}
+
+/// Check whether the given name is a builtin function or special form
+fn is_builtin(name: &str) -> bool {
+    RESERVED_WORDS.contains(&name)
+}
+
+/// Check whether the given name refers to a builtin, native or defined function
+fn is_function_name(name: &str, env: &Environment) -> bool {
+    let defined = env
+        .lookup(name)
+        .is_some_and(|(params, _)| !params.is_empty());
+    is_builtin(name) || defined || env.natives.contains_key(name)
+}
+
+/// Look up the parameters and body of a named function
+/// A variable holding a lambda resolves to the lambda's parameters and body
+fn lookup_function(name: &str, arity: usize, env: &Environment) -> Option<(Vec<String>, Rc<Expr>)> {
+    let function = match env.lookup_overload(name, arity) {
+        Some(clause) => clause.ok(),
+        None => env.lookup(name),
+    };
+    match function {
+        Some((params, value)) if params.is_empty() => lambda_binding(&value),
+        function => function,
+    }
+}
+
+/// Get the parameters and body of a lambda
+fn lambda_binding(expr: &Expr) -> Option<(Vec<String>, Rc<Expr>)> {
+    match expr {
+        Expr::Lambda { params, body } => Some((params.clone(), body.clone())),
+        _ => None,
+    }
+}
+
+/// Check whether the given symbol is a keyword such as :width
+fn is_keyword(symbol: &str) -> bool {
+    symbol.len() > 1 && symbol.starts_with(':')
+}
+
+/// Match keyword arguments to keyword parameters by name, in parameter order
+/// (make-window :height 24 :width 80)
+fn match_keyword_args(
+    params: &[String],
+    args: &[Rc<Expr>],
+) -> Result<Vec<(String, Rc<Expr>)>, String> {
+    if !args.len().is_multiple_of(2) {
+        return Err("Keyword arguments must be given as ':name value' pairs!".into());
+    }
+
+    let pairs = args
+        .chunks(2)
+        .map(|pair| match &*pair[0] {
+            Expr::Keyword(k) => {
+                let name = format!(":{}", k);
+                if params.contains(&name) {
+                    Ok((name, pair[1].clone()))
+                } else {
+                    Err(format!("Unknown keyword argument {}!", name))
+                }
+            }
+            _ => Err("Keyword arguments must be given as ':name value' pairs!".into()),
+        })
+        .collect::<Result<Vec<(String, Rc<Expr>)>, String>>()?;
+
+    params
+        .iter()
+        .map(|param| {
+            pairs
+                .iter()
+                .find(|(name, _)| name == param)
+                .map(|(_, value)| (param[1..].to_string(), value.clone()))
+                .ok_or_else(|| format!("Missing keyword argument {}!", param))
+        })
+        .collect()
 }
 
 /// Do mathematical operations
@@ -210,11 +938,23 @@ fn evaluate_symbol(
 /// (- 1 2 3)
 /// (* 1 2 3)
 /// (/ 1 2 3)
+/// (% 17 5)
+/// (+) and (*) return their identity elements 0 and 1
+/// (/ 7 2) is integer division when every number is an integer
+/// (+ 1/3 1/6) is exact when a fraction is involved and there are no floats
 fn do_math(vals: &[Rc<Expr>], env: &mut Environment, op: &str) -> EvalResult {
+    if op == "%" && vals.len() < 2 {
+        return EvalResult::Err("Remainder must be performed on at least two numbers!".into());
+    }
+
     if vals.is_empty() {
-        return EvalResult::Err(
-            "Mathematical operations must be performed on at least one number!".into(),
-        );
+        return match op {
+            "+" => EvalResult::Expr(Expr::int(0)),
+            "*" => EvalResult::Expr(Expr::int(1)),
+            _ => EvalResult::Err(
+                "Mathematical operations must be performed on at least one number!".into(),
+            ),
+        };
     }
 
     let total = vals
@@ -222,87 +962,191 @@ fn do_math(vals: &[Rc<Expr>], env: &mut Environment, op: &str) -> EvalResult {
         .map(|e| match eval(e.clone(), env) {
             EvalResult::Err(error) => Err(error),
             EvalResult::Expr(expr) => match &*expr {
-                Expr::FNum(n) => Ok(*n),
+                Expr::Int(_) | Expr::FNum(_) | Expr::Ratio(..) => Ok(expr),
                 _ => Err("Mathematical operations must be performed on numbers!".into()),
             },
             EvalResult::Unit => Err("Mathematical operations must be performed on numbers!".into()),
         })
-        .collect::<Result<Vec<f64>, String>>();
+        .collect::<Result<Vec<Rc<Expr>>, String>>();
 
-    total.map_or_else(
-        |error| EvalResult::Err(error),
-        |xs| {
-            let mut result = xs[0];
-            for x in xs.iter().skip(1) {
-                match op {
-                    "+" => result += x,
-                    "-" => result -= x,
-                    "*" => result *= x,
-                    "/" => result /= x,
-                    _ => return EvalResult::Err("Illegal mathematical operation!".into()),
-                }
-            }
-            EvalResult::Expr(Expr::fnum(result))
-        },
-    )
+    total
+        .and_then(|xs| fold_numbers(&xs, op))
+        .map_or_else(EvalResult::Err, EvalResult::Expr)
+}
+
+/// Fold evaluated numbers with an operator, exactly if they are all integers or a fraction
+/// is involved
+fn fold_numbers(xs: &[Rc<Expr>], op: &str) -> Result<Rc<Expr>, String> {
+    let ints = xs
+        .iter()
+        .map(|x| match **x {
+            Expr::Int(n) => Some(n),
+            _ => None,
+        })
+        .collect::<Option<Vec<i64>>>();
+    let exact = if let Some(ints) = ints {
+        do_int_math(&ints, op)?
+    } else if xs.iter().any(|x| matches!(**x, Expr::Ratio(..))) {
+        xs.iter()
+            .map(|x| x.as_fraction())
+            .collect::<Option<Vec<(i64, i64)>>>()
+            .map_or(Ok(None), |fractions| do_exact_math(&fractions, op))?
+    } else {
+        None
+    };
+    match exact {
+        Some(result) => Ok(result),
+        None => do_float_math(xs, op),
+    }
+}
+
+/// Do a common operation on a single number
+/// (add1 x) (sub1 x) (square x) (cube x)
+fn do_unary_math(vals: &[Rc<Expr>], env: &mut Environment, op: &str) -> EvalResult {
+    if vals.len() != 1 {
+        return EvalResult::Err(format!("Invalid {}! Must be '({} x)'!", op, op));
+    }
+
+    let x = eval_arg(&vals[0], env).and_then(|x| match &*x {
+        Expr::Int(_) | Expr::FNum(_) | Expr::Ratio(..) => Ok(x),
+        _ => Err("Mathematical operations must be performed on numbers!".into()),
+    });
+
+    x.and_then(|x| match op {
+        "add1" => fold_numbers(&[x, Expr::int(1)], "+"),
+        "sub1" => fold_numbers(&[x, Expr::int(1)], "-"),
+        "square" => fold_numbers(&[x.clone(), x], "*"),
+        "cube" => fold_numbers(&[x.clone(), x.clone(), x], "*"),
+        _ => Err("Illegal mathematical operation!".into()),
+    })
+    .map_or_else(EvalResult::Err, EvalResult::Expr)
+}
+
+/// Do mathematical operations on floats
+fn do_float_math(xs: &[Rc<Expr>], op: &str) -> Result<Rc<Expr>, String> {
+    let xs = xs.iter().filter_map(|x| x.as_f64()).collect::<Vec<f64>>();
+    let mut result = xs[0];
+    for x in xs.iter().skip(1) {
+        match op {
+            "+" => result += x,
+            "-" => result -= x,
+            "*" => result *= x,
+            "/" | "%" if *x == 0.0 => return Err("Division by zero!".into()),
+            "/" => result /= x,
+            "%" => result %= x,
+            _ => return Err("Illegal mathematical operation!".into()),
+        }
+    }
+    Ok(Expr::fnum(result))
+}
+
+/// Do mathematical operations on integers, or None if they overflow
+/// Division rounds toward zero
+fn do_int_math(xs: &[i64], op: &str) -> Result<Option<Rc<Expr>>, String> {
+    let mut result = Some(xs[0]);
+    for &x in xs.iter().skip(1) {
+        result = match (result, op) {
+            (Some(_), "/" | "%") if x == 0 => return Err("Division by zero!".into()),
+            (Some(n), "+") => n.checked_add(x),
+            (Some(n), "-") => n.checked_sub(x),
+            (Some(n), "*") => n.checked_mul(x),
+            (Some(n), "/") => n.checked_div(x),
+            (Some(n), "%") => n.checked_rem(x),
+            (None, _) => None,
+            _ => return Err("Illegal mathematical operation!".into()),
+        };
+    }
+    Ok(result.map(Expr::int))
+}
+
+/// Do mathematical operations on exact fractions, or None if they overflow
+/// Remainders of fractions are left to floating point
+fn do_exact_math(xs: &[(i64, i64)], op: &str) -> Result<Option<Rc<Expr>>, String> {
+    let mut result = Some(xs[0]);
+    for &(n2, d2) in xs.iter().skip(1) {
+        result = match (result, op) {
+            (Some((n1, d1)), "+") => n1
+                .checked_mul(d2)
+                .zip(n2.checked_mul(d1))
+                .and_then(|(a, b)| a.checked_add(b))
+                .zip(d1.checked_mul(d2)),
+            (Some((n1, d1)), "-") => n1
+                .checked_mul(d2)
+                .zip(n2.checked_mul(d1))
+                .and_then(|(a, b)| a.checked_sub(b))
+                .zip(d1.checked_mul(d2)),
+            (Some((n1, d1)), "*") => n1.checked_mul(n2).zip(d1.checked_mul(d2)),
+            (Some(_), "/" | "%") if n2 == 0 => return Err("Division by zero!".into()),
+            (Some((n1, d1)), "/") => n1.checked_mul(d2).zip(d1.checked_mul(n2)),
+            (Some(_), "%") | (None, _) => None,
+            _ => return Err("Illegal mathematical operation!".into()),
+        };
+    }
+    Ok(result.and_then(|(n, d)| Expr::ratio(n, d)))
 }
 
 /// Do boolean operations
 /// (or True True False)
 /// (and True True False)
 /// (not True)
+/// and stops at the first falsy value and or at the first truthy one, leaving the rest unevaluated
 fn do_boolean(vals: &[Rc<Expr>], env: &mut Environment, op: &str) -> EvalResult {
     if vals.is_empty() {
-        return EvalResult::Err("Boolean operations must be performed on at least value!".into());
+        return EvalResult::Err(
+            "Boolean operations must be performed on at least one value!".into(),
+        );
     }
 
     if op == "not" && vals.len() != 1 {
         return EvalResult::Err("Negation must be performed on one symbol!".into());
     }
 
-    let total = vals
-        .iter()
-        .map(|e| match eval(e.clone(), env) {
-            EvalResult::Err(error) => Err(error),
-            EvalResult::Expr(expr) => match &*expr {
-                Expr::Symbol(s) => match &s[..] {
-                    "False" => Ok(false),
-                    _ => Ok(true),
-                },
-                Expr::List(xs) => match xs[..] {
-                    [] => Ok(false),
-                    _ => Ok(true),
-                },
-                _ => Err("Boolean operations must be performed symbols!".into()),
-            },
-            EvalResult::Unit => Err("Boolean operations must be performed on symbols!".into()),
-        })
-        .collect::<Result<Vec<bool>, String>>();
-
-    total.map_or_else(
-        |error| EvalResult::Err(error),
-        |xs| {
-            let mut result = xs[0];
-            for x in xs.iter().skip(1) {
-                match op {
-                    "or" => result |= x,
-                    "and" => result &= x,
-                    _ => return EvalResult::Err("Illegal boolean operation!".into()),
-                }
-            }
-            if op == "not" {
-                match result {
-                    true => EvalResult::Expr(Expr::symbol("False".into())),
-                    false => EvalResult::Expr(Expr::symbol("True".into())),
-                }
-            } else {
-                match result {
-                    true => EvalResult::Expr(Expr::symbol("True".into())),
-                    false => EvalResult::Expr(Expr::symbol("False".into())),
-                }
+    // The value that decides the result as soon as an operand has it
+    let decisive = match op {
+        "or" => true,
+        "and" | "not" => false,
+        _ => return EvalResult::Err("Illegal boolean operation!".into()),
+    };
+
+    let mut result = !decisive;
+    for val in vals {
+        match is_truthy_arg(val, env) {
+            Err(error) => return EvalResult::Err(error),
+            Ok(x) if x == decisive => {
+                result = decisive;
+                break;
             }
-        },
-    )
+            Ok(_) => {}
+        }
+    }
+    if op == "not" {
+        result = !result;
+    }
+    match result {
+        true => EvalResult::Expr(Expr::symbol("True")),
+        false => EvalResult::Expr(Expr::symbol("False")),
+    }
+}
+
+/// Evaluate an operand of a boolean operation and check whether it is truthy
+fn is_truthy_arg(expr: &Rc<Expr>, env: &mut Environment) -> Result<bool, String> {
+    match eval(expr.clone(), env) {
+        EvalResult::Err(error) => Err(error),
+        EvalResult::Expr(expr) => Ok(is_truthy(&expr, env)),
+        EvalResult::Unit => Err("Boolean operations must be performed on values!".into()),
+    }
+}
+
+/// Check whether a value counts as true
+/// False and the empty list are falsy, and so is 0 when zero_is_falsy is on
+fn is_truthy(expr: &Expr, env: &Environment) -> bool {
+    match expr {
+        Expr::Symbol(s) => s != "False",
+        Expr::List(xs) => !xs.is_empty(),
+        Expr::Int(n) => !(env.zero_is_falsy && *n == 0),
+        Expr::FNum(n) => !(env.zero_is_falsy && *n == 0.0),
+        Expr::Keyword(_) | Expr::Str(_) | Expr::Ratio(..) | Expr::Lambda { .. } => true,
+    }
 }
 
 /// Do equality operations
@@ -324,27 +1168,113 @@ fn do_equality(vals: &[Rc<Expr>], env: &mut Environment, op: &str) -> EvalResult
         })
         .collect::<Result<Vec<Rc<Expr>>, String>>();
 
-    total.map_or_else(
-        |error| EvalResult::Err(error),
-        |xs| {
-            let first = &xs[0];
-            let result = match op {
-                "=" => xs.iter().all(|item| item == first),
-                "!=" => xs.iter().any(|item| item != first),
-                _ => return EvalResult::Err("Illegal equality operation!".into()),
+    total.map_or_else(EvalResult::Err, |xs| {
+        let first = &xs[0];
+        if let Some(other) = xs.iter().find(|x| type_name(x) != type_name(first)) {
+            env.warn(format!(
+                "Warning: comparing {} with {}!",
+                type_name(first),
+                type_name(other)
+            ));
+        }
+        let result = match op {
+            "=" => xs.iter().all(|item| item == first),
+            "!=" => xs.iter().any(|item| item != first),
+            _ => return EvalResult::Err("Illegal equality operation!".into()),
+        };
+        match result {
+            true => EvalResult::Expr(Expr::symbol("True")),
+            false => EvalResult::Expr(Expr::symbol("False")),
+        }
+    })
+}
+
+/// Do comparison operations on a chain of numbers
+/// (< 1 2 3)
+/// (>= 3 3 1)
+fn do_compare(vals: &[Rc<Expr>], env: &mut Environment, op: &str) -> EvalResult {
+    if vals.is_empty() {
+        return EvalResult::Err("Comparisons must be performed on at least one number!".into());
+    }
+
+    let total = vals
+        .iter()
+        .map(|e| match eval(e.clone(), env) {
+            EvalResult::Err(error) => Err(error),
+            EvalResult::Expr(expr) => expr
+                .as_f64()
+                .ok_or_else(|| "Comparisons must be performed on numbers!".into()),
+            EvalResult::Unit => Err("Comparisons must be performed on numbers!".into()),
+        })
+        .collect::<Result<Vec<f64>, String>>();
+
+    total.map_or_else(EvalResult::Err, |xs| {
+        let mut result = true;
+        for pair in xs.windows(2) {
+            result &= match op {
+                "<" => pair[0] < pair[1],
+                ">" => pair[0] > pair[1],
+                "<=" => pair[0] <= pair[1],
+                ">=" => pair[0] >= pair[1],
+                _ => return EvalResult::Err("Illegal comparison operation!".into()),
             };
-            match result {
-                true => EvalResult::Expr(Expr::symbol("True".into())),
-                false => EvalResult::Expr(Expr::symbol("False".into())),
-            }
+        }
+        match result {
+            true => EvalResult::Expr(Expr::symbol("True")),
+            false => EvalResult::Expr(Expr::symbol("False")),
+        }
+    })
+}
+
+/// Check whether two values are the very same allocation
+/// (identical? a b)
+fn identical(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.len() != 2 {
+        return EvalResult::Err("Invalid identical? Must be '(identical? a b)'!".into());
+    }
+
+    let a = resolve_arg(&vals[0], env);
+    let b = resolve_arg(&vals[1], env);
+
+    a.and_then(|a| b.map(|b| Rc::ptr_eq(&a, &b))).map_or_else(
+        EvalResult::Err,
+        |result| match result {
+            true => EvalResult::Expr(Expr::symbol("True")),
+            false => EvalResult::Expr(Expr::symbol("False")),
         },
     )
 }
 
+/// Check whether a value is a keyword
+/// (keyword? :foo)
+fn is_keyword_value(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.len() != 1 {
+        return EvalResult::Err("Invalid keyword? Must be '(keyword? x)'!".into());
+    }
+
+    eval_arg(&vals[0], env).map_or_else(EvalResult::Err, |expr| match &*expr {
+        Expr::Keyword(_) => EvalResult::Expr(Expr::symbol("True")),
+        _ => EvalResult::Expr(Expr::symbol("False")),
+    })
+}
+
+/// Resolve an argument to the value a variable is bound to, without re-evaluating it
+fn resolve_arg(expr: &Rc<Expr>, env: &mut Environment) -> Result<Rc<Expr>, String> {
+    if let Expr::Symbol(s) = &**expr {
+        if let Some((params, value)) = env.lookup(s) {
+            if params.is_empty() {
+                return Ok(value);
+            }
+        }
+    }
+    eval_arg(expr, env)
+}
+
 /// If statement
 /// (if (predicate) (then) (else))
+/// (if (predicate) (then))
 fn if_statement(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
-    if vals.len() != 3 {
+    if vals.len() != 2 && vals.len() != 3 {
         return EvalResult::Err(
             "Invalid if statement! Must be 'if (predicate) (then) (else)'".into(),
         );
@@ -352,35 +1282,145 @@ fn if_statement(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
 
     let predicate = &vals[0];
     let then = &vals[1];
-    let otherwise = &vals[2];
+    let otherwise = vals.get(2);
     let test = match eval(predicate.clone(), env) {
         EvalResult::Err(error) => Err(error),
-        EvalResult::Expr(expr) => match &*expr {
-            Expr::Symbol(s) => match &s[..] {
-                "False" => Ok(false),
-                _ => Ok(true),
-            },
-            Expr::List(xs) => match xs[..] {
-                [] => Ok(false),
-                _ => Ok(true),
-            },
-            _ => Err("Invalid if statement predicate!".into()),
-        },
+        EvalResult::Expr(expr) => Ok(is_truthy(&expr, env)),
         EvalResult::Unit => Err("If statement predicate cannot return Unit!".into()),
     };
 
-    test.map_or_else(
-        |error| EvalResult::Err(error),
-        |test| match test {
-            true => eval(then.clone(), env),
-            false => eval(otherwise.clone(), env),
-        },
-    )
+    test.map_or_else(EvalResult::Err, |test| match test {
+        true => eval(then.clone(), env),
+        false => otherwise.map_or(EvalResult::Unit, |otherwise| eval(otherwise.clone(), env)),
+    })
+}
+
+/// Evaluate expressions in order and return the result of the last one
+/// (begin (print 1) 42)
+fn begin(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    let mut result = EvalResult::Unit;
+    for val in vals {
+        result = eval(val.clone(), env);
+        if let EvalResult::Err(_) = result {
+            break;
+        }
+    }
+    result
+}
+
+/// Evaluate the body for as long as the predicate is true
+/// (while (> n 0) (set! n (- n 1)))
+/// (break) leaves the loop and (continue) skips to the next iteration
+fn while_loop(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.len() != 2 {
+        return EvalResult::Err("Invalid while loop! Must be '(while predicate body)'!".into());
+    }
+
+    loop {
+        match eval(vals[0].clone(), env) {
+            EvalResult::Err(error) => return EvalResult::Err(error),
+            EvalResult::Expr(expr) if is_truthy(&expr, env) => {}
+            EvalResult::Expr(_) => return EvalResult::Unit,
+            EvalResult::Unit => {
+                return EvalResult::Err("While loop predicate cannot return Unit!".into())
+            }
+        }
+
+        if let EvalResult::Err(error) = eval(vals[1].clone(), env) {
+            match env.signal.take() {
+                Some(Signal::Break) => return EvalResult::Unit,
+                Some(Signal::Continue) => {}
+                signal => {
+                    env.signal = signal;
+                    return EvalResult::Err(error);
+                }
+            }
+        }
+    }
+}
+
+/// Leave or restart the enclosing loop
+/// (break)
+/// (continue)
+fn loop_signal(vals: &[Rc<Expr>], env: &mut Environment, op: &str) -> EvalResult {
+    if !vals.is_empty() {
+        return EvalResult::Err(format!("Invalid {}! Must be '({})'!", op, op));
+    }
+
+    env.signal = Some(if op == "break" {
+        Signal::Break
+    } else {
+        Signal::Continue
+    });
+    EvalResult::Err(format!("Cannot use {} outside of a loop!", op))
+}
+
+/// Leave the enclosing function early with the given value
+/// (return 0)
+fn return_from_fn(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.len() != 1 {
+        return EvalResult::Err("Invalid return! Must be '(return value)'!".into());
+    }
+
+    eval_arg(&vals[0], env).map_or_else(EvalResult::Err, |value| {
+        env.signal = Some(Signal::Return(value));
+        EvalResult::Err("Cannot use return outside of a function!".into())
+    })
+}
+
+/// Evaluate the body of the first clause whose predicate is true
+/// (cond ((< x 0) -1) ((= x 0) 0) (True 1))
+fn cond(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    for clause in vals {
+        let (predicate, body) = match &**clause {
+            Expr::List(xs) if xs.len() == 2 => (&xs[0], &xs[1]),
+            _ => return EvalResult::Err("Invalid cond clause! Must be '(predicate body)'!".into()),
+        };
+
+        match eval(predicate.clone(), env) {
+            EvalResult::Err(error) => return EvalResult::Err(error),
+            EvalResult::Expr(expr) if is_truthy(&expr, env) => return eval(body.clone(), env),
+            EvalResult::Expr(_) => {}
+            EvalResult::Unit => {
+                return EvalResult::Err("Cond predicate cannot return Unit!".into())
+            }
+        }
+    }
+
+    EvalResult::Unit
+}
+
+/// Select one of two values, evaluating only the chosen one
+/// (select predicate a b)
+fn select(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.len() != 3 {
+        return EvalResult::Err("Invalid select! Must be '(select predicate a b)'!".into());
+    }
+
+    match eval(vals[0].clone(), env) {
+        EvalResult::Err(error) => EvalResult::Err(error),
+        EvalResult::Expr(expr) if is_truthy(&expr, env) => eval(vals[1].clone(), env),
+        EvalResult::Expr(_) => eval(vals[2].clone(), env),
+        EvalResult::Unit => EvalResult::Err("Select predicate cannot return Unit!".into()),
+    }
 }
 
 /// Add a variable to the enviroment
 /// (let x expr)
+/// (let x :number expr)
 fn add_var_to_env(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if let [name, annotation, value] = vals {
+        return match (&**name, &**annotation) {
+            (Expr::Symbol(name), Expr::Keyword(t)) if TYPE_NAMES.contains(&t.as_str()) => {
+                add_typed_var_to_env(name, t, value, env)
+            }
+            _ => EvalResult::Err(format!(
+                "Invalid typed variable definition! Must be 'let x :type expr' with a type of {}!",
+                TYPE_NAMES.join(", ")
+            )),
+        };
+    }
+
     if vals.len() != 2 {
         return EvalResult::Err("Invalid variable definition! Must be 'let x expr'!".into());
     }
@@ -389,9 +1429,8 @@ fn add_var_to_env(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
     let value = &vals[1];
 
     if let Expr::Symbol(s) = var_name {
-        let check = reserved_words(s);
-        if check.is_err() {
-            return EvalResult::Err(check.unwrap_err());
+        if let Err(error) = definable(s, env) {
+            return EvalResult::Err(error);
         }
     }
 
@@ -400,16 +1439,140 @@ fn add_var_to_env(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
             EvalResult::Err(error) => EvalResult::Err(error),
             EvalResult::Expr(e) => env
                 .add_var(s, e)
-                .map_or_else(|s| EvalResult::Err(s), |_| EvalResult::Unit),
+                .map_or_else(EvalResult::Err, |_| EvalResult::Unit),
+            EvalResult::Unit => EvalResult::Err("Cannot assign Unit to variable!".into()),
+        },
+        (Expr::List(_), expr) => match eval(expr.clone(), env) {
+            EvalResult::Err(error) => EvalResult::Err(error),
+            EvalResult::Expr(e) => {
+                let mut bindings = Vec::new();
+                destructure(var_name, e, &mut bindings, env)
+                    .and_then(|_| {
+                        bindings
+                            .iter()
+                            .try_for_each(|(name, value)| env.add_var(name, value.clone()))
+                    })
+                    .map_or_else(EvalResult::Err, |_| EvalResult::Unit)
+            }
             EvalResult::Unit => EvalResult::Err("Cannot assign Unit to variable!".into()),
         },
         _ => EvalResult::Err("Invalid variable definition! Must be 'let x expr!".into()),
     }
 }
 
+/// Update an existing variable in whichever context binds it
+/// (set! x expr)
+fn set_var_in_env(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    let name = match vals {
+        [name, _] => match &**name {
+            Expr::Symbol(s) => s,
+            _ => return EvalResult::Err("Invalid assignment! Must be '(set! x expr)'!".into()),
+        },
+        _ => return EvalResult::Err("Invalid assignment! Must be '(set! x expr)'!".into()),
+    };
+
+    if let Err(error) = definable(name, env) {
+        return EvalResult::Err(error);
+    }
+
+    match eval(vals[1].clone(), env) {
+        EvalResult::Err(error) => EvalResult::Err(error),
+        EvalResult::Expr(e) => env
+            .set_var(name, e)
+            .map_or_else(EvalResult::Err, |_| EvalResult::Unit),
+        EvalResult::Unit => EvalResult::Err("Cannot assign Unit to variable!".into()),
+    }
+}
+
+/// Add a constant to the enviroment
+/// (defconst x expr)
+fn add_const_to_env(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.len() != 2 {
+        return EvalResult::Err("Invalid constant definition! Must be 'defconst x expr'!".into());
+    }
+
+    let name = match &*vals[0] {
+        Expr::Symbol(s) => s,
+        _ => {
+            return EvalResult::Err(
+                "Invalid constant definition! Must be 'defconst x expr'!".into(),
+            )
+        }
+    };
+
+    if let Err(error) = definable(name, env) {
+        return EvalResult::Err(error);
+    }
+
+    match eval(vals[1].clone(), env) {
+        EvalResult::Err(error) => EvalResult::Err(error),
+        EvalResult::Expr(e) => env
+            .add_const(name, e)
+            .map_or_else(EvalResult::Err, |_| EvalResult::Unit),
+        EvalResult::Unit => EvalResult::Err("Cannot assign Unit to constant!".into()),
+    }
+}
+
+/// Add a variable to the enviroment after checking the type of its value
+/// (let x :number expr)
+fn add_typed_var_to_env(
+    name: &str,
+    expected: &str,
+    value: &Rc<Expr>,
+    env: &mut Environment,
+) -> EvalResult {
+    if let Err(error) = definable(name, env) {
+        return EvalResult::Err(error);
+    }
+
+    match eval(value.clone(), env) {
+        EvalResult::Err(error) => EvalResult::Err(error),
+        EvalResult::Expr(e) if type_name(&e) == expected => env
+            .add_var(name, e)
+            .map_or_else(EvalResult::Err, |_| EvalResult::Unit),
+        EvalResult::Expr(e) => EvalResult::Err(format!(
+            "Type mismatch! {} must be a {} but got a {}!",
+            name,
+            expected,
+            type_name(&e)
+        )),
+        EvalResult::Unit => EvalResult::Err("Cannot assign Unit to variable!".into()),
+    }
+}
+
+/// Match a destructuring pattern against a value, collecting the bindings
+/// (let (a (b c)) (1 (2 3)))
+fn destructure(
+    pattern: &Expr,
+    value: Rc<Expr>,
+    bindings: &mut Vec<(String, Rc<Expr>)>,
+    env: &Environment,
+) -> Result<(), String> {
+    match (pattern, &*value) {
+        (Expr::Symbol(s), _) => {
+            definable(s, env)?;
+            bindings.push((s.to_string(), value.clone()));
+            Ok(())
+        }
+        (Expr::List(names), Expr::List(values)) if names.len() == values.len() => names
+            .iter()
+            .zip(values)
+            .try_for_each(|(name, value)| destructure(name, value.clone(), bindings, env)),
+        (Expr::List(_), _) => {
+            Err("Destructuring pattern does not match the shape of the value!".into())
+        }
+        _ => Err("Destructuring patterns must only contain symbols and lists!".into()),
+    }
+}
+
 /// Add a function to the enviroment
 /// (fn my-func (args) body)
+/// (fn my-func ((x) body) ((x y) body))
 fn add_fun_to_env(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.len() >= 2 && is_fn_clause(&vals[1]) {
+        return add_overloaded_fun_to_env(vals, env);
+    }
+
     if vals.len() != 3 {
         return EvalResult::Err(
             "Invalid function definition! Must be '(fn my-func (args) body)'!".into(),
@@ -421,33 +1584,22 @@ fn add_fun_to_env(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
     let body = &vals[2];
 
     if let Expr::Symbol(s) = fn_name {
-        let check = reserved_words(s);
-        if check.is_err() {
-            return EvalResult::Err(check.unwrap_err());
+        if let Err(error) = definable(s, env) {
+            return EvalResult::Err(error);
         }
     }
 
-    match (&*fn_name, args, body) {
+    match (fn_name, args, body) {
         (Expr::Symbol(fn_name), Expr::List(args), body) => {
             let params = args
                 .iter()
-                .cloned()
-                .map(|e| {
-                    if let Expr::Symbol(n) = &*e {
-                        Ok(n.to_string())
-                    } else {
-                        Err("Function arguments must be strings!".into())
-                    }
-                })
+                .map(|e| param_name(e))
                 .collect::<Result<Vec<String>, String>>();
 
-            params.map_or_else(
-                |error| EvalResult::Err(error),
-                |params| {
-                    env.add_fn(fn_name, &params, body.clone())
-                        .map_or_else(|error| EvalResult::Err(error), |_| EvalResult::Unit)
-                },
-            )
+            params.map_or_else(EvalResult::Err, |params| {
+                env.add_fn(fn_name, &params, body.clone())
+                    .map_or_else(EvalResult::Err, |_| EvalResult::Unit)
+            })
         }
         _ => EvalResult::Err(
             "Invalid function definition! Must be '(fn my-func (args) body)'!".into(),
@@ -455,22 +1607,252 @@ fn add_fun_to_env(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
     }
 }
 
+/// Add a function that caches its results to the environment
+/// (defmemo my-func (args) body)
+fn add_memo_fun_to_env(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    let (name, params, body) = match vals {
+        [name, params, body] => match (&**name, &**params) {
+            (Expr::Symbol(name), Expr::List(params)) if !is_fn_clause(&vals[1]) => {
+                (name, params, body)
+            }
+            _ => {
+                return EvalResult::Err(
+                    "Invalid function definition! Must be '(defmemo my-func (args) body)'!".into(),
+                )
+            }
+        },
+        _ => {
+            return EvalResult::Err(
+                "Invalid function definition! Must be '(defmemo my-func (args) body)'!".into(),
+            )
+        }
+    };
+
+    if let Err(error) = definable(name, env) {
+        return EvalResult::Err(error);
+    }
+
+    params
+        .iter()
+        .map(|e| param_name(e))
+        .collect::<Result<Vec<String>, String>>()
+        .and_then(|params| env.add_memoized_fn(name, &params, body.clone()))
+        .map_or_else(EvalResult::Err, |_| EvalResult::Unit)
+}
+
+/// Create an anonymous function
+/// (lambda (x y) (+ x y))
+fn lambda(vals: &[Rc<Expr>]) -> EvalResult {
+    let (params, body) = match vals {
+        [params, body] => match &**params {
+            Expr::List(params) => (params, body),
+            _ => return EvalResult::Err("Invalid lambda! Must be '(lambda (args) body)'!".into()),
+        },
+        _ => return EvalResult::Err("Invalid lambda! Must be '(lambda (args) body)'!".into()),
+    };
+
+    params
+        .iter()
+        .map(|param| param_name(param))
+        .collect::<Result<Vec<String>, String>>()
+        .map_or_else(EvalResult::Err, |params| {
+            EvalResult::Expr(Rc::new(Expr::Lambda {
+                params,
+                body: body.clone(),
+            }))
+        })
+}
+
+/// Get the name of a function parameter, keeping the colon of keyword parameters
+fn param_name(expr: &Expr) -> Result<String, String> {
+    match expr {
+        Expr::Symbol(n) => Ok(n.to_string()),
+        Expr::Keyword(k) => Ok(format!(":{}", k)),
+        _ => Err("Function arguments must be strings!".into()),
+    }
+}
+
+/// Check whether an expression is a '((args) body)' clause of an overloaded function
+fn is_fn_clause(expr: &Expr) -> bool {
+    match expr {
+        Expr::List(xs) => matches!(xs.first().map(|x| &**x), Some(Expr::List(_))),
+        _ => false,
+    }
+}
+
+/// Add a function with one definition per number of arguments to the environment
+/// (fn my-func ((x) body) ((x y) body))
+fn add_overloaded_fun_to_env(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    let fn_name = match &*vals[0] {
+        Expr::Symbol(s) => s,
+        _ => {
+            return EvalResult::Err(
+                "Invalid function definition! Must be '(fn my-func ((args) body) ...)'!".into(),
+            )
+        }
+    };
+
+    if let Err(error) = definable(fn_name, env) {
+        return EvalResult::Err(error);
+    }
+
+    let clauses = vals[1..]
+        .iter()
+        .map(|clause| match &**clause {
+            Expr::List(xs) if xs.len() == 2 => match &*xs[0] {
+                Expr::List(args) => args
+                    .iter()
+                    .map(|e| param_name(e))
+                    .collect::<Result<Vec<String>, String>>()
+                    .map(|params| (params, xs[1].clone())),
+                _ => Err("Invalid function clause! Must be '((args) body)'!".into()),
+            },
+            _ => Err("Invalid function clause! Must be '((args) body)'!".into()),
+        })
+        .collect::<Result<Vec<Binding>, String>>();
+
+    let clauses = clauses.and_then(|clauses| {
+        for (i, (params, _)) in clauses.iter().enumerate() {
+            if clauses[..i]
+                .iter()
+                .any(|(other, _)| other.len() == params.len())
+            {
+                return Err(format!(
+                    "Function {} has more than one definition taking {} arguments!",
+                    fn_name,
+                    params.len()
+                ));
+            }
+        }
+        Ok(clauses)
+    });
+
+    clauses.map_or_else(EvalResult::Err, |clauses| {
+        env.add_overloaded_fn(fn_name, &clauses)
+            .map_or_else(EvalResult::Err, |_| EvalResult::Unit)
+    })
+}
+
+/// Types that a variable can be annotated with
+const TYPE_NAMES: &[&str] = &["number", "symbol", "list", "string", "keyword"];
+
+/// Names of all builtin operators, special forms and functions
+const RESERVED_WORDS: &[&str] = &[
+    "+",
+    "-",
+    "*",
+    "/",
+    "%",
+    "add1",
+    "sub1",
+    "square",
+    "cube",
+    "or",
+    "and",
+    "not",
+    "=",
+    "!=",
+    "<",
+    ">",
+    "<=",
+    ">=",
+    "if",
+    "let",
+    "fn",
+    "print",
+    "symbol-append",
+    "quote",
+    "quasiquote",
+    "unquote",
+    "unquote-splicing",
+    "current-time-ms",
+    "elapsed",
+    "inspect",
+    "compare",
+    "butlast",
+    "last-n",
+    "select",
+    "cond",
+    "begin",
+    "while",
+    "equal?",
+    "identical?",
+    "defconst",
+    "set!",
+    "split-at",
+    "interleave",
+    "interpose",
+    "abs",
+    "sign",
+    "clamp",
+    "keyword?",
+    "with-timeout",
+    "diff",
+    "spy",
+    "min-list",
+    "max-list",
+    "rotate",
+    "shuffle",
+    "seed-rng",
+    "list",
+    "concat",
+    "map",
+    "scan",
+    "lambda",
+    "defmemo",
+    "warnings",
+    "car",
+    "cdr",
+    "cons",
+    "string-lines",
+    "string-words",
+    "return",
+    "break",
+    "continue",
+    "parse-int",
+    "parse-float",
+    "flat-map",
+    "unfold",
+    "string-repeat",
+    "string-reverse",
+    "string-starts-with?",
+    "string-ends-with?",
+    "dbg",
+    "any-of",
+    "all-of",
+    "partition-by",
+    "chunk-while",
+    "tree-map",
+    "iterate",
+];
+
 // Reserved words
 fn reserved_words(symbol: &str) -> Result<(), String> {
-    let reserved = [
-        "+", "-", "*", "/", "or", "and", "not", "=", "!=", "if", "let", "fn", "print",
-    ];
-    if reserved.contains(&symbol) {
+    if RESERVED_WORDS.contains(&symbol) {
         Err("Reserved variable or function name!".into())
     } else {
         Ok(())
     }
 }
 
+/// Check that a definition does not take the name of a builtin or of a native function,
+/// since calls dispatch to those first and would never reach the definition
+fn definable(symbol: &str, env: &Environment) -> Result<(), String> {
+    reserved_words(symbol)?;
+    if env.natives.contains_key(symbol) {
+        Err(format!(
+            "{} is a native function and cannot be redefined!",
+            symbol
+        ))
+    } else {
+        Ok(())
+    }
+}
+
 /// Print function
 /// (print 1 2 3)
 fn print(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
-    if vals.len() < 1 {
+    if vals.is_empty() {
         return EvalResult::Err("Missing values in print function'!".into());
     }
 
@@ -486,19 +1868,1285 @@ fn print(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
 
 /// Generate output printed to stdout when the user calls print
 pub fn gen_print_output(expr: Rc<Expr>, env: &mut Environment) -> String {
+    print_output(expr, env, 0, &mut Vec::new())
+}
+
+/// Generate print output for an expression found at the given nesting depth
+/// Resolving a variable does not nest deeper, so the variables being resolved are tracked
+/// to stop on one that refers back to itself
+fn print_output(
+    expr: Rc<Expr>,
+    env: &mut Environment,
+    depth: usize,
+    resolving: &mut Vec<String>,
+) -> String {
+    if depth >= env.max_print_depth {
+        return "...".into();
+    }
+
     match &*expr {
-        Expr::Symbol(s) => match env.lookup(&s) {
-            None => format!("{}", s),
-            Some((params, e)) if params.is_empty() => gen_print_output(e, env),
-            _ => format!("<func-object: {}>", s.to_string()),
+        Expr::Symbol(s) if is_function_name(s, env) => "<function>".into(),
+        Expr::Symbol(s) if resolving.contains(s) => "...".into(),
+        Expr::Symbol(s) => match env.lookup(s) {
+            Some((_, e)) => {
+                resolving.push(s.to_string());
+                let output = print_output(e, env, depth, resolving);
+                resolving.pop();
+                output
+            }
+            None => s.to_string(),
         },
+        Expr::Keyword(k) => format!(":{}", k),
+        Expr::Str(s) => s.to_string(),
+        Expr::Int(n) => format!("{}", n),
         Expr::FNum(n) => format!("{}", n),
+        Expr::Ratio(n, d) => format!("{}/{}", n, d),
+        Expr::Lambda { .. } => "<lambda>".into(),
         Expr::List(xs) => {
             let output = xs
                 .iter()
-                .map(|e| gen_print_output(e.clone(), env))
+                .map(|e| print_output(e.clone(), env, depth + 1, resolving))
                 .collect::<Vec<String>>();
             format!("({})", output.join(" "))
         }
     }
 }
+
+/// Print a labelled value to stderr and return the value unchanged
+/// (spy mid (* 2 3))
+fn spy(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.len() != 2 {
+        return EvalResult::Err("Invalid spy! Must be '(spy label expr)'!".into());
+    }
+
+    let label = eval_arg(&vals[0], env);
+    let value = eval_arg(&vals[1], env);
+
+    label
+        .and_then(|label| value.map(|value| (label, value)))
+        .map_or_else(EvalResult::Err, |(label, value)| {
+            eprintln!("{}", gen_spy_output(label, value.clone(), env));
+            EvalResult::Expr(value)
+        })
+}
+
+/// Generate output printed to stderr when the user calls spy
+pub fn gen_spy_output(label: Rc<Expr>, value: Rc<Expr>, env: &mut Environment) -> String {
+    format!(
+        "{}: {}",
+        gen_print_output(label, env),
+        gen_print_output(value, env)
+    )
+}
+
+/// Print an expression and its value to stderr and return the value unchanged
+/// (dbg (+ 1 2))
+fn dbg(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.len() != 1 {
+        return EvalResult::Err("Invalid dbg! Must be '(dbg expr)'!".into());
+    }
+
+    match eval(vals[0].clone(), env) {
+        EvalResult::Expr(value) => {
+            eprintln!("{}", gen_dbg_output(&vals[0], &value, env));
+            EvalResult::Expr(value)
+        }
+        EvalResult::Unit => EvalResult::Err("Cannot dbg Unit!".into()),
+        error => error,
+    }
+}
+
+/// Generate output printed to stderr when the user calls dbg
+pub fn gen_dbg_output(source: &Expr, value: &Expr, env: &Environment) -> String {
+    format!(
+        "{} = {}",
+        source.render(env.max_print_depth),
+        value.render(env.max_print_depth)
+    )
+}
+
+/// Inspect function
+/// (inspect (1 a))
+fn inspect(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.len() != 1 {
+        return EvalResult::Err("Invalid inspect! Must be '(inspect expr)'!".into());
+    }
+
+    match eval(vals[0].clone(), env) {
+        EvalResult::Expr(expr) => {
+            println!("{}", gen_inspect_output(&expr));
+            EvalResult::Expr(expr)
+        }
+        EvalResult::Unit => EvalResult::Err("Cannot inspect Unit!".into()),
+        error => error,
+    }
+}
+
+/// Generate output printed to stdout when the user calls inspect
+pub fn gen_inspect_output(expr: &Expr) -> String {
+    match expr {
+        Expr::List(xs) => {
+            let mut lines = vec![format!("list, length {}", xs.len())];
+            lines.extend(
+                xs.iter()
+                    .enumerate()
+                    .map(|(i, x)| format!("  {}: {}", i, type_name(x))),
+            );
+            lines.join("\n")
+        }
+        _ => type_name(expr).to_string(),
+    }
+}
+
+/// List the warnings collected so far
+/// (warnings)
+fn warnings(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if !vals.is_empty() {
+        return EvalResult::Err("Invalid warnings! Must be '(warnings)'!".into());
+    }
+
+    let warnings = env
+        .warnings
+        .iter()
+        .map(|warning| Expr::string(warning))
+        .collect::<Vec<Rc<Expr>>>();
+    EvalResult::Expr(Expr::list(&warnings))
+}
+
+/// Get the name of the type of an expression
+pub fn type_name(expr: &Expr) -> &'static str {
+    match expr {
+        Expr::Symbol(_) => "symbol",
+        Expr::Keyword(_) => "keyword",
+        Expr::Str(_) => "string",
+        Expr::Int(_) | Expr::FNum(_) | Expr::Ratio(..) => "number",
+        Expr::List(_) => "list",
+        Expr::Lambda { .. } => "function",
+    }
+}
+
+/// Get the name of the type of the value an expression evaluates to
+/// Names of builtins and defined functions are reported as function
+pub fn type_of(expr: Rc<Expr>, env: &mut Environment) -> Result<&'static str, String> {
+    if let Expr::Symbol(s) = &*expr {
+        if is_function_name(s, env) {
+            return Ok("function");
+        }
+    }
+
+    env.error_trace = None;
+    let result = eval(expr, env);
+    env.take_error_trace();
+    match result {
+        EvalResult::Err(error) => Err(error),
+        EvalResult::Expr(e) => Ok(type_name(&e)),
+        EvalResult::Unit => Ok("unit"),
+    }
+}
+
+/// Concatenate the names of symbols and the contents of strings into a new symbol
+/// (symbol-append get- "x")
+fn symbol_append(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.is_empty() {
+        return EvalResult::Err(
+            "Symbol concatenation must be performed on at least one symbol!".into(),
+        );
+    }
+
+    let names = vals
+        .iter()
+        .map(|e| match eval(e.clone(), env) {
+            EvalResult::Err(error) => Err(error),
+            EvalResult::Expr(expr) => match &*expr {
+                Expr::Symbol(s) | Expr::Str(s) => Ok(s.to_string()),
+                _ => Err("Symbol concatenation must be performed on symbols or strings!".into()),
+            },
+            EvalResult::Unit => {
+                Err("Symbol concatenation must be performed on symbols or strings!".into())
+            }
+        })
+        .collect::<Result<Vec<String>, String>>();
+
+    names.map_or_else(EvalResult::Err, |names| {
+        EvalResult::Expr(Expr::symbol(&names.concat()))
+    })
+}
+
+/// Return an expression without evaluating it
+/// (quote (a b c))
+fn quote(vals: &[Rc<Expr>]) -> EvalResult {
+    match vals {
+        [expr] => EvalResult::Expr(expr.clone()),
+        _ => EvalResult::Err("Invalid quote! Must be '(quote expr)'!".into()),
+    }
+}
+
+/// Quasiquote a template, evaluating only its unquoted parts
+/// (quasiquote (a (unquote (+ 1 2)) (unquote-splicing xs) b))
+fn quasiquote(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.len() != 1 {
+        return EvalResult::Err("Invalid quasiquote! Must be '(quasiquote template)'!".into());
+    }
+
+    expand_quasiquote(vals[0].clone(), env).map_or_else(EvalResult::Err, EvalResult::Expr)
+}
+
+/// Recursively build a quasiquoted template
+fn expand_quasiquote(template: Rc<Expr>, env: &mut Environment) -> Result<Rc<Expr>, String> {
+    match &*template {
+        Expr::List(xs) if is_form(xs, "unquote") => eval_unquoted(xs, env),
+        Expr::List(xs) if is_form(xs, "unquote-splicing") => {
+            Err("Cannot use unquote-splicing outside of a list!".into())
+        }
+        Expr::List(xs) => {
+            let mut items = Vec::new();
+            for x in xs {
+                match &**x {
+                    Expr::List(ys) if is_form(ys, "unquote-splicing") => {
+                        match &*eval_unquoted(ys, env)? {
+                            Expr::List(spliced) => items.extend(spliced.iter().cloned()),
+                            _ => return Err("Cannot splice a value that is not a list!".into()),
+                        }
+                    }
+                    _ => items.push(expand_quasiquote(x.clone(), env)?),
+                }
+            }
+            Ok(Expr::list(&items))
+        }
+        _ => Ok(template),
+    }
+}
+
+/// Check whether a list is a call to the given special form
+fn is_form(xs: &[Rc<Expr>], name: &str) -> bool {
+    matches!(xs.first().map(|x| &**x), Some(Expr::Symbol(s)) if s == name)
+}
+
+/// Evaluate the expression inside an unquote or unquote-splicing form
+fn eval_unquoted(xs: &[Rc<Expr>], env: &mut Environment) -> Result<Rc<Expr>, String> {
+    if xs.len() != 2 {
+        return Err("Unquote must be performed on exactly one expression!".into());
+    }
+
+    match eval(xs[1].clone(), env) {
+        EvalResult::Err(error) => Err(error),
+        EvalResult::Expr(expr) => Ok(expr),
+        EvalResult::Unit => Err("Cannot unquote Unit!".into()),
+    }
+}
+
+/// Get the current Unix time in milliseconds
+/// (current-time-ms)
+fn current_time_ms(vals: &[Rc<Expr>]) -> EvalResult {
+    if !vals.is_empty() {
+        return EvalResult::Err("Invalid current-time-ms! Must be '(current-time-ms)'!".into());
+    }
+
+    unix_time_ms().map_or_else(EvalResult::Err, |now| EvalResult::Expr(Expr::fnum(now)))
+}
+
+/// Get the milliseconds passed since a time returned by current-time-ms
+/// (elapsed start)
+fn elapsed(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.len() != 1 {
+        return EvalResult::Err("Invalid elapsed! Must be '(elapsed start)'!".into());
+    }
+
+    let start = match eval(vals[0].clone(), env) {
+        EvalResult::Err(error) => return EvalResult::Err(error),
+        EvalResult::Expr(expr) => match expr.as_f64() {
+            Some(n) => n,
+            None => return EvalResult::Err("Elapsed time must be measured from a number!".into()),
+        },
+        EvalResult::Unit => {
+            return EvalResult::Err("Elapsed time must be measured from a number!".into())
+        }
+    };
+
+    unix_time_ms().map_or_else(EvalResult::Err, |now| {
+        EvalResult::Expr(Expr::fnum(now - start))
+    })
+}
+
+/// Evaluate an expression, failing if it takes longer than the given milliseconds
+/// (with-timeout 100 expr)
+fn with_timeout(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.len() != 2 {
+        return EvalResult::Err("Invalid with-timeout! Must be '(with-timeout ms expr)'!".into());
+    }
+
+    let ms = match eval_number(&vals[0], env) {
+        Ok(ms) if ms >= 0.0 => ms,
+        Ok(_) => return EvalResult::Err("Timeout must not be negative!".into()),
+        Err(error) => return EvalResult::Err(error),
+    };
+
+    let outer = env.deadline;
+    let deadline = Duration::try_from_secs_f64(ms / 1000.0)
+        .ok()
+        .and_then(|timeout| Instant::now().checked_add(timeout));
+    env.deadline = match (outer, deadline) {
+        (Some(outer), Some(deadline)) => Some(outer.min(deadline)),
+        (outer, deadline) => outer.or(deadline),
+    };
+    let result = eval(vals[1].clone(), env);
+    env.deadline = outer;
+    result
+}
+
+/// Read the system clock as milliseconds since the Unix epoch
+fn unix_time_ms() -> Result<f64, String> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as f64)
+        .map_err(|_| "System clock is set before the Unix epoch!".into())
+}
+
+/// Compare two values, returning -1, 0 or 1
+/// (compare 1 2)
+/// (compare a b)
+fn compare(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.len() != 2 {
+        return EvalResult::Err("Invalid compare! Must be '(compare a b)'!".into());
+    }
+
+    let args = vals
+        .iter()
+        .map(|e| match eval(e.clone(), env) {
+            EvalResult::Err(error) => Err(error),
+            EvalResult::Expr(expr) => Ok(expr),
+            EvalResult::Unit => Err("Cannot compare Unit!".into()),
+        })
+        .collect::<Result<Vec<Rc<Expr>>, String>>();
+
+    let ordering = args.and_then(|args| match (&*args[0], &*args[1]) {
+        (Expr::Symbol(a), Expr::Symbol(b)) => Ok(a.cmp(b)),
+        (Expr::Keyword(a), Expr::Keyword(b)) => Ok(a.cmp(b)),
+        (Expr::Str(a), Expr::Str(b)) => Ok(a.cmp(b)),
+        (a, b) => match (a.as_f64(), b.as_f64()) {
+            (Some(a), Some(b)) => a
+                .partial_cmp(&b)
+                .ok_or_else(|| "Cannot compare NaN!".to_string()),
+            _ => Err("Comparison must be performed on two numbers or two symbols!".into()),
+        },
+    });
+
+    ordering.map_or_else(EvalResult::Err, |ordering| {
+        let n = match ordering {
+            Ordering::Less => -1,
+            Ordering::Equal => 0,
+            Ordering::Greater => 1,
+        };
+        EvalResult::Expr(Expr::int(n))
+    })
+}
+
+/// Describe where two structures differ, as a list of (value path a b) and
+/// (length path len-a len-b) entries where path is the list of indexes leading there
+/// (diff (1 2 3) (1 9 3))
+fn diff(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.len() != 2 {
+        return EvalResult::Err("Invalid diff! Must be '(diff a b)'!".into());
+    }
+
+    let a = eval_arg(&vals[0], env);
+    let b = eval_arg(&vals[1], env);
+
+    a.and_then(|a| b.map(|b| (a, b)))
+        .map_or_else(EvalResult::Err, |(a, b)| {
+            let mut differences = Vec::new();
+            diff_exprs(&a, &b, &mut Vec::new(), &mut differences);
+            EvalResult::Expr(Expr::list(&differences))
+        })
+}
+
+/// Collect the differences between two expressions found below the given path
+fn diff_exprs(a: &Rc<Expr>, b: &Rc<Expr>, path: &mut Vec<usize>, differences: &mut Vec<Rc<Expr>>) {
+    let location = |path: &[usize]| {
+        Expr::list(
+            &path
+                .iter()
+                .map(|i| Expr::int(*i as i64))
+                .collect::<Vec<Rc<Expr>>>(),
+        )
+    };
+
+    match (&**a, &**b) {
+        (Expr::List(xs), Expr::List(ys)) => {
+            for (i, (x, y)) in xs.iter().zip(ys).enumerate() {
+                path.push(i);
+                diff_exprs(x, y, path, differences);
+                path.pop();
+            }
+            if xs.len() != ys.len() {
+                differences.push(Expr::list(&[
+                    Expr::symbol("length"),
+                    location(path),
+                    Expr::int(xs.len() as i64),
+                    Expr::int(ys.len() as i64),
+                ]));
+            }
+        }
+        _ if a == b => {}
+        _ => differences.push(Expr::list(&[
+            Expr::symbol("value"),
+            location(path),
+            a.clone(),
+            b.clone(),
+        ])),
+    }
+}
+
+/// Build a list from the values of its arguments
+/// (list 1 2 (+ 1 2))
+fn list(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    vals.iter()
+        .map(|val| eval_arg(val, env))
+        .collect::<Result<Vec<Rc<Expr>>, String>>()
+        .map_or_else(EvalResult::Err, |xs| EvalResult::Expr(Expr::list(&xs)))
+}
+
+/// Split a string into lines or into words
+/// (string-lines "a\nb") splits on \n or \r\n, without an empty line after a trailing newline
+/// (string-words " a  b ") splits on runs of whitespace, ignoring leading and trailing whitespace
+fn split_string(vals: &[Rc<Expr>], env: &mut Environment, op: &str) -> EvalResult {
+    if vals.len() != 1 {
+        return EvalResult::Err(format!("Invalid {}! Must be '({} string)'!", op, op));
+    }
+
+    eval_string(&vals[0], env).map_or_else(EvalResult::Err, |s| {
+        let parts = match op {
+            "string-lines" => s.lines().map(Expr::string).collect::<Vec<Rc<Expr>>>(),
+            _ => s.split_whitespace().map(Expr::string).collect(),
+        };
+        EvalResult::Expr(Expr::list(&parts))
+    })
+}
+
+/// Repeat a string n times
+/// (string-repeat "ab" 3)
+fn string_repeat(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.len() != 2 {
+        return EvalResult::Err(
+            "Invalid string-repeat! Must be '(string-repeat string n)'!".into(),
+        );
+    }
+
+    let s = eval_string(&vals[0], env);
+    let n = eval_count(&vals[1], env);
+
+    s.and_then(|s| n.map(|n| Expr::string(&s.repeat(n))))
+        .map_or_else(EvalResult::Err, EvalResult::Expr)
+}
+
+/// Reverse the characters of a string
+/// (string-reverse "abc")
+fn string_reverse(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.len() != 1 {
+        return EvalResult::Err(
+            "Invalid string-reverse! Must be '(string-reverse string)'!".into(),
+        );
+    }
+
+    eval_string(&vals[0], env)
+        .map(|s| Expr::string(&s.chars().rev().collect::<String>()))
+        .map_or_else(EvalResult::Err, EvalResult::Expr)
+}
+
+/// Check whether a string begins or ends with another string
+/// (string-starts-with? "hello" "he")
+/// (string-ends-with? "hello" "lo")
+fn string_affix(vals: &[Rc<Expr>], env: &mut Environment, op: &str) -> EvalResult {
+    if vals.len() != 2 {
+        return EvalResult::Err(format!("Invalid {}! Must be '({} string affix)'!", op, op));
+    }
+
+    let s = eval_string(&vals[0], env);
+    let affix = eval_string(&vals[1], env);
+
+    s.and_then(|s| {
+        affix.map(|affix| match op {
+            "string-starts-with?" => s.starts_with(&affix),
+            _ => s.ends_with(&affix),
+        })
+    })
+    .map_or_else(EvalResult::Err, |found| {
+        EvalResult::Expr(Expr::symbol(if found { "True" } else { "False" }))
+    })
+}
+
+/// Parse a string as a whole number in the given base, 10 by default
+/// (parse-int "42")
+/// (parse-int "ff" 16)
+fn parse_int(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.len() != 1 && vals.len() != 2 {
+        return EvalResult::Err("Invalid parse-int! Must be '(parse-int string radix)'!".into());
+    }
+
+    let s = eval_string(&vals[0], env);
+    let radix = match vals.get(1) {
+        Some(radix) => eval_count(radix, env).and_then(|radix| match radix {
+            2..=36 => Ok(radix as u32),
+            _ => Err("Radix must be between 2 and 36!".into()),
+        }),
+        None => Ok(10),
+    };
+
+    s.and_then(|s| radix.map(|radix| (s, radix)))
+        .and_then(|(s, radix)| {
+            i64::from_str_radix(&s, radix)
+                .map(Expr::int)
+                .map_err(|_| format!("Cannot parse {:?} as an integer in base {}!", s, radix))
+        })
+        .map_or_else(EvalResult::Err, EvalResult::Expr)
+}
+
+/// Parse a string as a floating point number
+/// (parse-float "3.14")
+fn parse_float(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.len() != 1 {
+        return EvalResult::Err("Invalid parse-float! Must be '(parse-float string)'!".into());
+    }
+
+    eval_string(&vals[0], env)
+        .and_then(|s| {
+            s.parse::<f64>()
+                .map(Expr::fnum)
+                .map_err(|_| format!("Cannot parse {:?} as a float!", s))
+        })
+        .map_or_else(EvalResult::Err, EvalResult::Expr)
+}
+
+/// Apply a one-argument function to every element of a list
+/// (map double (1 2 3))
+/// (map add1 (1 2 3))
+/// (map (lambda (x) (* x x)) (1 2 3))
+fn map(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.len() != 2 {
+        return EvalResult::Err("Invalid map! Must be '(map function list)'!".into());
+    }
+
+    let function = match function_arg(&vals[0], env) {
+        Ok(function) => function,
+        Err(error) => return EvalResult::Err(error),
+    };
+    if !accepts_args(&function, 1, env) {
+        return EvalResult::Err(format!(
+            "Map must be given a function of one argument, got {}!",
+            function
+        ));
+    }
+
+    eval_list(&vals[1], env)
+        .and_then(|xs| {
+            xs.into_iter()
+                .map(|x| match apply_value(&function, &[x], env) {
+                    EvalResult::Err(error) => Err(error),
+                    EvalResult::Expr(expr) => Ok(expr),
+                    EvalResult::Unit => Err(format!("{} returned Unit inside map!", function)),
+                })
+                .collect::<Result<Vec<Rc<Expr>>, String>>()
+        })
+        .map_or_else(EvalResult::Err, |xs| EvalResult::Expr(Expr::list(&xs)))
+}
+
+/// Apply a list-producing function to every element of a list and join the results
+/// (flat-map (lambda (x) (list x x)) (1 2))
+fn flat_map(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.len() != 2 {
+        return EvalResult::Err("Invalid flat-map! Must be '(flat-map function list)'!".into());
+    }
+
+    let function = match function_arg(&vals[0], env) {
+        Ok(function) => function,
+        Err(error) => return EvalResult::Err(error),
+    };
+    if !accepts_args(&function, 1, env) {
+        return EvalResult::Err(format!(
+            "Flat-map must be given a function of one argument, got {}!",
+            function
+        ));
+    }
+
+    eval_list(&vals[1], env)
+        .and_then(|xs| {
+            let mut results = Vec::new();
+            for x in xs {
+                match apply_value(&function, &[x], env) {
+                    EvalResult::Err(error) => return Err(error),
+                    EvalResult::Expr(expr) => match &*expr {
+                        Expr::List(ys) => results.extend(ys.iter().cloned()),
+                        e => {
+                            return Err(format!(
+                                "{} must return a list inside flat-map, got {}!",
+                                function, e
+                            ))
+                        }
+                    },
+                    EvalResult::Unit => {
+                        return Err(format!("{} returned Unit inside flat-map!", function))
+                    }
+                }
+            }
+            Ok(results)
+        })
+        .map_or_else(EvalResult::Err, |xs| EvalResult::Expr(Expr::list(&xs)))
+}
+
+/// Build a list from a seed, where f turns a seed into (value next-seed) until pred holds
+/// (unfold (lambda (s) (list s (+ s 1))) 1 (lambda (s) (> s 5)))
+fn unfold(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.len() != 3 {
+        return EvalResult::Err(
+            "Invalid unfold! Must be '(unfold function seed predicate)'!".into(),
+        );
+    }
+
+    let function = function_arg(&vals[0], env);
+    let seed = eval_arg(&vals[1], env);
+    let predicate = function_arg(&vals[2], env);
+
+    function
+        .and_then(|function| seed.map(|seed| (function, seed)))
+        .and_then(|(function, seed)| predicate.map(|predicate| (function, seed, predicate)))
+        .and_then(|(function, mut seed, predicate)| {
+            let mut values = Vec::new();
+            for _ in 0..UNFOLD_LIMIT {
+                let done = apply_arg(&predicate, &[seed.clone()], "unfold", env)?;
+                if is_truthy(&done, env) {
+                    return Ok(values);
+                }
+                let step = apply_arg(&function, &[seed], "unfold", env)?;
+                match &*step {
+                    Expr::List(pair) if pair.len() == 2 => {
+                        values.push(pair[0].clone());
+                        seed = pair[1].clone();
+                    }
+                    e => {
+                        return Err(format!(
+                            "{} must return (value next-seed) inside unfold, got {}!",
+                            function, e
+                        ))
+                    }
+                }
+            }
+            Err(format!(
+                "Unfold did not stop within {} steps!",
+                UNFOLD_LIMIT
+            ))
+        })
+        .map_or_else(EvalResult::Err, |xs| EvalResult::Expr(Expr::list(&xs)))
+}
+
+/// Build a predicate that holds when any or all of the given predicates hold for its argument
+/// (any-of keyword? (lambda (x) (> x 10)))
+fn combine_predicates(vals: &[Rc<Expr>], env: &mut Environment, op: &str) -> EvalResult {
+    if vals.is_empty() {
+        return EvalResult::Err(format!("Invalid {}! Must be '({} predicate...)'!", op, op));
+    }
+
+    let connective = if op == "any-of" { "or" } else { "and" };
+    let arg = Expr::symbol("x");
+    vals.iter()
+        .map(|val| {
+            // A variable holding a lambda is resolved now, since it may be out of scope when called
+            let predicate = match &**val {
+                Expr::Symbol(name) if !is_function_name(name, env) => eval_arg(val, env)?,
+                _ => function_arg(val, env)?,
+            };
+            if accepts_args(&predicate, 1, env) {
+                Ok(Expr::list(&[predicate, arg.clone()]))
+            } else {
+                Err(format!(
+                    "{} must be given predicates of one argument, got {}!",
+                    op, predicate
+                ))
+            }
+        })
+        .collect::<Result<Vec<Rc<Expr>>, String>>()
+        .map_or_else(EvalResult::Err, |calls| {
+            let body = std::iter::once(Expr::symbol(connective))
+                .chain(calls)
+                .collect::<Vec<Rc<Expr>>>();
+            EvalResult::Expr(Rc::new(Expr::Lambda {
+                params: vec!["x".into()],
+                body: Expr::list(&body),
+            }))
+        })
+}
+
+/// Split a list into runs of consecutive elements for which a function gives equal results
+/// (partition-by (lambda (x) (% x 2)) (1 3 2 4 5))
+fn partition_by(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.len() != 2 {
+        return EvalResult::Err(
+            "Invalid partition-by! Must be '(partition-by function list)'!".into(),
+        );
+    }
+
+    let function = match function_arg(&vals[0], env) {
+        Ok(function) => function,
+        Err(error) => return EvalResult::Err(error),
+    };
+    if !accepts_args(&function, 1, env) {
+        return EvalResult::Err(format!(
+            "Partition-by must be given a function of one argument, got {}!",
+            function
+        ));
+    }
+
+    eval_list(&vals[1], env)
+        .and_then(|xs| {
+            let mut runs: Vec<Vec<Rc<Expr>>> = Vec::new();
+            let mut last_key = None;
+            for x in xs {
+                let key = apply_arg(&function, std::slice::from_ref(&x), "partition-by", env)?;
+                match runs.last_mut() {
+                    Some(run) if last_key.as_ref() == Some(&key) => run.push(x),
+                    _ => runs.push(vec![x]),
+                }
+                last_key = Some(key);
+            }
+            Ok(runs)
+        })
+        .map_or_else(EvalResult::Err, |runs| {
+            let runs = runs.iter().map(|run| Expr::list(run)).collect::<Vec<_>>();
+            EvalResult::Expr(Expr::list(&runs))
+        })
+}
+
+/// Split a list before every element for which a predicate of the previous and current element fails
+/// (chunk-while < (1 2 3 2 4))
+fn chunk_while(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.len() != 2 {
+        return EvalResult::Err(
+            "Invalid chunk-while! Must be '(chunk-while predicate list)'!".into(),
+        );
+    }
+
+    let predicate = match function_arg(&vals[0], env) {
+        Ok(predicate) => predicate,
+        Err(error) => return EvalResult::Err(error),
+    };
+    if !accepts_args(&predicate, 2, env) {
+        return EvalResult::Err(format!(
+            "Chunk-while must be given a predicate of two arguments, got {}!",
+            predicate
+        ));
+    }
+
+    eval_list(&vals[1], env)
+        .and_then(|xs| {
+            let mut chunks: Vec<Vec<Rc<Expr>>> = Vec::new();
+            for x in xs {
+                if let Some(chunk) = chunks.last_mut() {
+                    let prev = chunk[chunk.len() - 1].clone();
+                    let keep = apply_arg(&predicate, &[prev, x.clone()], "chunk-while", env)?;
+                    if is_truthy(&keep, env) {
+                        chunk.push(x);
+                        continue;
+                    }
+                }
+                chunks.push(vec![x]);
+            }
+            Ok(chunks)
+        })
+        .map_or_else(EvalResult::Err, |chunks| {
+            let chunks = chunks
+                .iter()
+                .map(|chunk| Expr::list(chunk))
+                .collect::<Vec<_>>();
+            EvalResult::Expr(Expr::list(&chunks))
+        })
+}
+
+/// Apply a function to every atom of a nested list, keeping the shape of the tree
+/// (tree-map add1 (1 (2 3)))
+fn tree_map(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.len() != 2 {
+        return EvalResult::Err("Invalid tree-map! Must be '(tree-map function tree)'!".into());
+    }
+
+    let function = match function_arg(&vals[0], env) {
+        Ok(function) => function,
+        Err(error) => return EvalResult::Err(error),
+    };
+    if !accepts_args(&function, 1, env) {
+        return EvalResult::Err(format!(
+            "Tree-map must be given a function of one argument, got {}!",
+            function
+        ));
+    }
+
+    eval_arg(&vals[1], env)
+        .and_then(|tree| map_leaves(&function, tree, env))
+        .map_or_else(EvalResult::Err, EvalResult::Expr)
+}
+
+/// Rebuild a tree with a function applied to each of its atoms
+fn map_leaves(function: &Expr, tree: Rc<Expr>, env: &mut Environment) -> Result<Rc<Expr>, String> {
+    match &*tree {
+        Expr::List(xs) => xs
+            .iter()
+            .map(|x| map_leaves(function, x.clone(), env))
+            .collect::<Result<Vec<Rc<Expr>>, String>>()
+            .map(|xs| Expr::list(&xs)),
+        _ => apply_arg(function, &[tree], "tree-map", env),
+    }
+}
+
+/// List the first n values of x, (f x), (f (f x)) and so on
+/// (iterate (lambda (x) (* x 2)) 1 4)
+fn iterate(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.len() != 3 {
+        return EvalResult::Err("Invalid iterate! Must be '(iterate function value n)'!".into());
+    }
+
+    let function = function_arg(&vals[0], env);
+    let value = eval_arg(&vals[1], env);
+    let n = eval_count(&vals[2], env);
+
+    function
+        .and_then(|function| value.map(|value| (function, value)))
+        .and_then(|(function, value)| n.map(|n| (function, value, n)))
+        .and_then(|(function, mut value, n)| {
+            let mut values = Vec::with_capacity(n);
+            for i in 0..n {
+                if i > 0 {
+                    value = apply_arg(&function, &[value], "iterate", env)?;
+                }
+                values.push(value.clone());
+            }
+            Ok(values)
+        })
+        .map_or_else(EvalResult::Err, |xs| EvalResult::Expr(Expr::list(&xs)))
+}
+
+/// Most elements unfold generates before giving up on a predicate that never holds
+const UNFOLD_LIMIT: usize = 100_000;
+
+/// Apply a function given to a higher-order builtin, which must return a value
+fn apply_arg(
+    function: &Expr,
+    args: &[Rc<Expr>],
+    op: &str,
+    env: &mut Environment,
+) -> Result<Rc<Expr>, String> {
+    match apply_value(function, args, env) {
+        EvalResult::Err(error) => Err(error),
+        EvalResult::Expr(expr) => Ok(expr),
+        EvalResult::Unit => Err(format!("{} returned Unit inside {}!", function, op)),
+    }
+}
+
+/// Fold a list with a binary function, keeping every intermediate result
+/// (scan + 0 (1 2 3))
+fn scan(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.len() != 3 {
+        return EvalResult::Err("Invalid scan! Must be '(scan function init list)'!".into());
+    }
+
+    let function = match function_arg(&vals[0], env) {
+        Ok(function) => function,
+        Err(error) => return EvalResult::Err(error),
+    };
+
+    let init = eval_arg(&vals[1], env);
+    let xs = eval_list(&vals[2], env);
+
+    init.and_then(|init| xs.map(|xs| (init, xs)))
+        .and_then(|(init, xs)| {
+            let mut results = vec![init];
+            for x in xs {
+                let acc = results[results.len() - 1].clone();
+                match apply_value(&function, &[acc, x], env) {
+                    EvalResult::Err(error) => return Err(error),
+                    EvalResult::Expr(expr) => results.push(expr),
+                    EvalResult::Unit => {
+                        return Err(format!("{} returned Unit inside scan!", function))
+                    }
+                }
+            }
+            Ok(results)
+        })
+        .map_or_else(EvalResult::Err, |xs| EvalResult::Expr(Expr::list(&xs)))
+}
+
+/// Join lists or strings into one of the same kind as the first argument
+/// (concat (1 2) (3))
+/// (concat "ab" "c")
+fn concat(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    let args = vals
+        .iter()
+        .map(|val| eval_arg(val, env))
+        .collect::<Result<Vec<Rc<Expr>>, String>>();
+
+    let mismatch = |expected: &str, found: &Expr| {
+        format!(
+            "Cannot concatenate a {} onto a {}!",
+            type_name(found),
+            expected
+        )
+    };
+
+    args.and_then(|args| match args.first().map(|first| &**first) {
+        None => Ok(Expr::list(&[])),
+        Some(Expr::List(_)) => args
+            .iter()
+            .map(|arg| match &**arg {
+                Expr::List(xs) => Ok(xs.clone()),
+                e => Err(mismatch("list", e)),
+            })
+            .collect::<Result<Vec<Vec<Rc<Expr>>>, String>>()
+            .map(|xss| Expr::list(&xss.concat())),
+        Some(Expr::Str(_)) => args
+            .iter()
+            .map(|arg| match &**arg {
+                Expr::Str(s) => Ok(s.as_str()),
+                e => Err(mismatch("string", e)),
+            })
+            .collect::<Result<Vec<&str>, String>>()
+            .map(|strings| Expr::string(&strings.concat())),
+        Some(e) => Err(format!("Cannot concatenate a {}!", type_name(e))),
+    })
+    .map_or_else(EvalResult::Err, EvalResult::Expr)
+}
+
+/// Get the first element of a list
+/// (car (1 2 3))
+fn car(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.len() != 1 {
+        return EvalResult::Err("Invalid car! Must be '(car list)'!".into());
+    }
+
+    eval_list(&vals[0], env)
+        .and_then(|xs| {
+            xs.first()
+                .cloned()
+                .ok_or_else(|| "Cannot take car of an empty list!".into())
+        })
+        .map_or_else(EvalResult::Err, EvalResult::Expr)
+}
+
+/// Get all but the first element of a list
+/// (cdr (1 2 3))
+fn cdr(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.len() != 1 {
+        return EvalResult::Err("Invalid cdr! Must be '(cdr list)'!".into());
+    }
+
+    eval_list(&vals[0], env)
+        .and_then(|xs| match xs.is_empty() {
+            true => Err("Cannot take cdr of an empty list!".into()),
+            false => Ok(Expr::list(&xs[1..])),
+        })
+        .map_or_else(EvalResult::Err, EvalResult::Expr)
+}
+
+/// Prepend an element to a list
+/// (cons 1 (2 3))
+fn cons(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.len() != 2 {
+        return EvalResult::Err("Invalid cons! Must be '(cons x list)'!".into());
+    }
+
+    let x = eval_arg(&vals[0], env);
+    let xs = eval_list(&vals[1], env);
+
+    x.and_then(|x| {
+        xs.map(|mut xs| {
+            xs.insert(0, x);
+            Expr::list(&xs)
+        })
+    })
+    .map_or_else(EvalResult::Err, EvalResult::Expr)
+}
+
+/// Get all but the last element of a list
+/// (butlast (1 2 3))
+fn butlast(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.len() != 1 {
+        return EvalResult::Err("Invalid butlast! Must be '(butlast list)'!".into());
+    }
+
+    eval_list(&vals[0], env).map_or_else(EvalResult::Err, |xs| {
+        EvalResult::Expr(Expr::list(&xs[..xs.len().saturating_sub(1)]))
+    })
+}
+
+/// Get the last n elements of a list
+/// (last-n 2 (1 2 3 4))
+fn last_n(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.len() != 2 {
+        return EvalResult::Err("Invalid last-n! Must be '(last-n n list)'!".into());
+    }
+
+    let n = eval_count(&vals[0], env);
+    let xs = eval_list(&vals[1], env);
+
+    n.and_then(|n| xs.map(|xs| xs[xs.len().saturating_sub(n)..].to_vec()))
+        .map_or_else(EvalResult::Err, |xs| EvalResult::Expr(Expr::list(&xs)))
+}
+
+/// Split a list into its first n elements and the rest
+/// (split-at 2 (1 2 3 4))
+fn split_at(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.len() != 2 {
+        return EvalResult::Err("Invalid split-at! Must be '(split-at n list)'!".into());
+    }
+
+    let n = eval_count(&vals[0], env);
+    let xs = eval_list(&vals[1], env);
+
+    n.and_then(|n| {
+        xs.map(|xs| {
+            let (front, back) = xs.split_at(n.min(xs.len()));
+            Expr::list(&[Expr::list(front), Expr::list(back)])
+        })
+    })
+    .map_or_else(EvalResult::Err, EvalResult::Expr)
+}
+
+/// Take elements from each list in turn, stopping at the end of the shortest
+/// (interleave (1 2 3) (a b c))
+fn interleave(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.is_empty() {
+        return EvalResult::Err("Invalid interleave! Must be '(interleave list ...)'!".into());
+    }
+
+    vals.iter()
+        .map(|val| eval_list(val, env))
+        .collect::<Result<Vec<Vec<Rc<Expr>>>, String>>()
+        .map_or_else(EvalResult::Err, |lists| {
+            let shortest = lists.iter().map(|xs| xs.len()).min().unwrap_or(0);
+            let xs = (0..shortest)
+                .flat_map(|i| lists.iter().map(move |xs| xs[i].clone()))
+                .collect::<Vec<Rc<Expr>>>();
+            EvalResult::Expr(Expr::list(&xs))
+        })
+}
+
+/// Insert a separator between consecutive elements of a list
+/// (interpose 0 (1 2 3))
+fn interpose(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.len() != 2 {
+        return EvalResult::Err("Invalid interpose! Must be '(interpose sep list)'!".into());
+    }
+
+    let sep = eval_arg(&vals[0], env);
+    let xs = eval_list(&vals[1], env);
+
+    sep.and_then(|sep| {
+        xs.map(|xs| {
+            let mut output = Vec::with_capacity((2 * xs.len()).saturating_sub(1));
+            for (i, x) in xs.into_iter().enumerate() {
+                if i > 0 {
+                    output.push(sep.clone());
+                }
+                output.push(x);
+            }
+            output
+        })
+    })
+    .map_or_else(EvalResult::Err, |xs| EvalResult::Expr(Expr::list(&xs)))
+}
+
+/// Shift a list cyclically left by n, or right for a negative n
+/// (rotate 1 (1 2 3))
+fn rotate(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.len() != 2 {
+        return EvalResult::Err("Invalid rotate! Must be '(rotate n list)'!".into());
+    }
+
+    let n = eval_number(&vals[0], env).and_then(|n| match n.fract() == 0.0 {
+        true => Ok(n),
+        false => Err("Expected a whole number argument!".into()),
+    });
+    let xs = eval_list(&vals[1], env);
+
+    n.and_then(|n| {
+        xs.map(|mut xs| {
+            if !xs.is_empty() {
+                let len = xs.len() as f64;
+                xs.rotate_left(n.rem_euclid(len) as usize);
+            }
+            xs
+        })
+    })
+    .map_or_else(EvalResult::Err, |xs| EvalResult::Expr(Expr::list(&xs)))
+}
+
+/// Get a randomly permuted copy of a list
+/// (shuffle (1 2 3))
+fn shuffle(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.len() != 1 {
+        return EvalResult::Err("Invalid shuffle! Must be '(shuffle list)'!".into());
+    }
+
+    eval_list(&vals[0], env).map_or_else(EvalResult::Err, |mut xs| {
+        for i in (1..xs.len()).rev() {
+            let j = (env.next_random() % (i as u64 + 1)) as usize;
+            xs.swap(i, j);
+        }
+        EvalResult::Expr(Expr::list(&xs))
+    })
+}
+
+/// Seed the random number generator used by shuffle
+/// (seed-rng 42)
+fn seed_rng(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.len() != 1 {
+        return EvalResult::Err("Invalid seed-rng! Must be '(seed-rng n)'!".into());
+    }
+
+    eval_count(&vals[0], env).map_or_else(EvalResult::Err, |seed| {
+        env.seed_rng(seed as u64);
+        EvalResult::Unit
+    })
+}
+
+/// Get the absolute value of a number
+/// (abs -5)
+fn abs(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.len() != 1 {
+        return EvalResult::Err("Invalid abs! Must be '(abs x)'!".into());
+    }
+
+    eval_arg(&vals[0], env)
+        .and_then(|x| match &*x {
+            Expr::Int(n) if *n != i64::MIN => Ok(Expr::int(n.abs())),
+            Expr::Ratio(n, d) if *n != i64::MIN => Ok(Rc::new(Expr::Ratio(n.abs(), *d))),
+            _ => x
+                .as_f64()
+                .map(|n| Expr::fnum(n.abs()))
+                .ok_or_else(|| "Expected a number argument!".into()),
+        })
+        .map_or_else(EvalResult::Err, EvalResult::Expr)
+}
+
+/// Get -1, 0 or 1 depending on the sign of a number
+/// (sign -5)
+fn sign(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.len() != 1 {
+        return EvalResult::Err("Invalid sign! Must be '(sign x)'!".into());
+    }
+
+    eval_arg(&vals[0], env)
+        .and_then(|x| match &*x {
+            Expr::Int(n) => Ok(Expr::int(n.signum())),
+            Expr::Ratio(n, _) => Ok(Expr::int(n.signum())),
+            _ => x
+                .as_f64()
+                .map(|n| match n.partial_cmp(&0.0) {
+                    Some(Ordering::Less) => Expr::fnum(-1.0),
+                    Some(Ordering::Greater) => Expr::fnum(1.0),
+                    _ => Expr::fnum(0.0),
+                })
+                .ok_or_else(|| "Expected a number argument!".into()),
+        })
+        .map_or_else(EvalResult::Err, EvalResult::Expr)
+}
+
+/// Constrain a number to the range from lo to hi
+/// (clamp x lo hi)
+fn clamp(vals: &[Rc<Expr>], env: &mut Environment) -> EvalResult {
+    if vals.len() != 3 {
+        return EvalResult::Err("Invalid clamp! Must be '(clamp x lo hi)'!".into());
+    }
+
+    let bounds = vals
+        .iter()
+        .map(|val| eval_arg(val, env))
+        .collect::<Result<Vec<Rc<Expr>>, String>>();
+
+    bounds.map_or_else(EvalResult::Err, |xs| {
+        let ints = xs
+            .iter()
+            .map(|x| match **x {
+                Expr::Int(n) => Some(n),
+                _ => None,
+            })
+            .collect::<Option<Vec<i64>>>();
+        let floats = xs.iter().map(|x| x.as_f64()).collect::<Option<Vec<f64>>>();
+        match (ints, floats) {
+            (_, None) => EvalResult::Err("Expected a number argument!".into()),
+            (_, Some(xs)) if xs[1] > xs[2] => {
+                EvalResult::Err("Lower bound of clamp must not exceed upper bound!".into())
+            }
+            (Some(ns), _) => EvalResult::Expr(Expr::int(ns[0].max(ns[1]).min(ns[2]))),
+            (None, Some(xs)) => EvalResult::Expr(Expr::fnum(xs[0].max(xs[1]).min(xs[2]))),
+        }
+    })
+}
+
+/// Get the smallest or largest number in a list
+/// (min-list (3 1 4))
+/// (max-list (3 1 4))
+fn extremum(vals: &[Rc<Expr>], env: &mut Environment, op: &str) -> EvalResult {
+    if vals.len() != 1 {
+        return EvalResult::Err(format!("Invalid {}! Must be '({} list)'!", op, op));
+    }
+
+    let xs = eval_list(&vals[0], env).and_then(|xs| {
+        xs.iter()
+            .map(|x| {
+                x.as_f64()
+                    .map(|n| (n, x.clone()))
+                    .ok_or_else(|| format!("{} must be performed on a list of numbers!", op))
+            })
+            .collect::<Result<Vec<(f64, Rc<Expr>)>, String>>()
+    });
+
+    xs.and_then(|xs| {
+        let best = xs.into_iter().reduce(|best, x| match op {
+            "min-list" if x.0 < best.0 => x,
+            "max-list" if x.0 > best.0 => x,
+            _ => best,
+        });
+        best.map(|(_, x)| x)
+            .ok_or_else(|| format!("Cannot get {} of an empty list!", op))
+    })
+    .map_or_else(EvalResult::Err, EvalResult::Expr)
+}
+
+/// Evaluate a builtin argument, rejecting Unit
+fn eval_arg(expr: &Rc<Expr>, env: &mut Environment) -> Result<Rc<Expr>, String> {
+    match eval(expr.clone(), env) {
+        EvalResult::Err(error) => Err(error),
+        EvalResult::Expr(expr) => Ok(expr),
+        EvalResult::Unit => Err("Cannot pass Unit as an argument to a function!".into()),
+    }
+}
+
+/// Evaluate a builtin argument that must be a list
+fn eval_list(expr: &Rc<Expr>, env: &mut Environment) -> Result<Vec<Rc<Expr>>, String> {
+    eval_arg(expr, env).and_then(|expr| match &*expr {
+        Expr::List(xs) => Ok(xs.clone()),
+        _ => Err("Expected a list argument!".into()),
+    })
+}
+
+/// Evaluate a builtin argument that must be a string
+fn eval_string(expr: &Rc<Expr>, env: &mut Environment) -> Result<String, String> {
+    eval_arg(expr, env).and_then(|expr| match &*expr {
+        Expr::Str(s) => Ok(s.to_string()),
+        _ => Err("Expected a string argument!".into()),
+    })
+}
+
+/// Evaluate a builtin argument that must be a number
+fn eval_number(expr: &Rc<Expr>, env: &mut Environment) -> Result<f64, String> {
+    eval_arg(expr, env).and_then(|expr| {
+        expr.as_f64()
+            .ok_or_else(|| "Expected a number argument!".into())
+    })
+}
+
+/// Evaluate a builtin argument that must be a non-negative whole number
+fn eval_count(expr: &Rc<Expr>, env: &mut Environment) -> Result<usize, String> {
+    eval_arg(expr, env).and_then(|expr| match &*expr {
+        Expr::Int(n) if *n >= 0 => Ok(*n as usize),
+        Expr::FNum(n) if *n >= 0.0 && n.fract() == 0.0 => Ok(*n as usize),
+        _ => Err("Expected a non-negative whole number argument!".into()),
+    })
+}
@@ -1,28 +1,97 @@
-use crate::eval::{eval, Environment, EvalResult};
-use crate::lex::lex;
-use crate::parse::parse;
+use crate::eval::{eval, type_of, Environment, EvalResult};
+use crate::lex::{lex, lex_with_spans, LexError, Token};
+use crate::parse::{parse, parse_program, parse_program_with_spans};
+use crate::types::Expr;
 
 use std::fs;
 use std::io;
 use std::io::Write;
+use std::rc::Rc;
 
 /// Interactive REPL.
 pub fn repl() {
     println!("Welcome to RustLisp!");
+    let mut env = Environment::default();
+    let mut input = String::new();
     loop {
-        print!(">>> ");
+        print!("{}", if input.is_empty() { ">>> " } else { "... " });
         io::stdout().flush().unwrap();
-        let mut input = String::new();
-        // Read user input
+        // Read user input, continuing on the next line until it is complete
         io::stdin()
             .read_line(&mut input)
-            .ok()
             .expect("Failed to read user input!");
+        if let Some(output) = run_meta_command(input.trim(), &mut env) {
+            println!("{}", output);
+            input.clear();
+            continue;
+        }
+        if !is_complete(&input) {
+            continue;
+        }
+        let program = std::mem::take(&mut input);
         // Interpret the input
-        match run_interpreter(&input.trim()) {
-            EvalResult::Err(error) => println!("{}", error),
-            _ => continue,
+        let result = run_interpreter_with_env(program.trim(), &mut env);
+        env.take_warnings()
+            .iter()
+            .for_each(|warning| eprintln!("{}", warning));
+        if let EvalResult::Err(error) = result {
+            println!("{}", error)
+        }
+    }
+}
+
+/// Runs a REPL command such as :depth, or returns None if the input is not one.
+pub fn run_meta_command(input: &str, env: &mut Environment) -> Option<String> {
+    if let Some(program) = input.strip_prefix(":type ") {
+        let expr = lex(program)
+            .map_err(|error| format!("Lex error: {:?}", error))
+            .and_then(|tokens| parse(&tokens).map_err(|error| format!("Parse error: {:?}", error)));
+        return Some(match expr.and_then(|expr| type_of(expr, env)) {
+            Ok(name) => name.to_string(),
+            Err(error) => error,
+        });
+    }
+
+    match input {
+        ":depth" => {
+            let mut names = env
+                .contexts
+                .first()
+                .map_or_else(Vec::new, |context| context.keys().cloned().collect());
+            names.sort();
+            let mut output = format!(
+                "Contexts: {}\nDefined: {}",
+                env.num_contexts(),
+                names.join(" ")
+            );
+            if !env.call_stack.is_empty() {
+                output.push_str(&format!("\nCall stack: {}", env.call_stack.join(" -> ")));
+            }
+            Some(output)
+        }
+        _ => None,
+    }
+}
+
+/// Checks whether the input holds complete expressions, with every string closed and
+/// every parenthesis balanced. Parentheses inside strings and comments do not count.
+/// A stray ) makes the input complete so that its error is reported right away.
+pub fn is_complete(input: &str) -> bool {
+    match lex(input) {
+        Ok(tokens) => {
+            let mut depth = 0;
+            for token in tokens.iter() {
+                match token {
+                    Token::LPar => depth += 1,
+                    Token::RPar if depth == 0 => return true,
+                    Token::RPar => depth -= 1,
+                    _ => {}
+                }
+            }
+            depth == 0
         }
+        Err(LexError::UnterminatedString) => false,
+        Err(_) => true,
     }
 }
 
@@ -32,25 +101,89 @@ pub fn file(path: &str) {
     match fs::read_to_string(path) {
         Ok(content) => {
             // Print the returned result only if it is an error
-            match run_interpreter(&content) {
-                EvalResult::Err(error) => println!("{}", error),
-                _ => return,
+            let mut env = Environment::default();
+            let result = run_interpreter_with_env(&content, &mut env);
+            env.take_warnings()
+                .iter()
+                .for_each(|warning| eprintln!("{}", warning));
+            if let EvalResult::Err(error) = result {
+                println!("{}", error)
             }
         }
         Err(_) => println!("Unable to open the file!"),
     }
 }
 
-/// Lexes, parses, and evaluates the given program.
+/// Lexes, parses, and evaluates the given program in a fresh environment.
 pub fn run_interpreter(program: &str) -> EvalResult {
-    match lex(program) {
-        Ok(tokens) => match parse(&tokens) {
-            Ok(expr) => {
-                let mut env = Environment::default();
-                eval(expr.clone(), &mut env)
+    run_interpreter_with_env(program, &mut Environment::default())
+}
+
+/// Lexes, parses, and evaluates the given program in the given environment.
+/// Top-level expressions are evaluated in order and the last result is returned.
+pub fn run_interpreter_with_env(program: &str, env: &mut Environment) -> EvalResult {
+    let exprs = match lex_with_spans(program) {
+        Ok((tokens, spans)) => match parse_program_with_spans(&tokens, &spans) {
+            Ok(exprs) => exprs,
+            Err((error, Some(span))) => {
+                return EvalResult::Err(format!("Parse error: {}: {:?}", span, error))
             }
-            Err(error) => EvalResult::Err(format!("Parse error: {:?}", error)),
+            Err((error, None)) => return EvalResult::Err(format!("Parse error: {:?}", error)),
         },
-        Err(error) => EvalResult::Err(format!("Lex error: {:?}", error)),
+        Err((error, span)) => return EvalResult::Err(format!("Lex error: {}: {:?}", span, error)),
+    };
+
+    declare_functions(&exprs, env);
+
+    let mut result = EvalResult::Unit;
+    for expr in exprs {
+        // Drop any trace or control flow left over from an earlier evaluation
+        env.error_trace = None;
+        env.signal = None;
+        result = eval(expr, env);
+        if let EvalResult::Err(error) = result {
+            return match env.take_error_trace() {
+                Some(trace) => {
+                    EvalResult::Err(format!("{}\nCall stack: {}", error, trace.join(" -> ")))
+                }
+                None => EvalResult::Err(error),
+            };
+        }
     }
+    result
+}
+
+/// Defines every top-level function whose name is not bound yet before anything runs,
+/// so a call can come before the definition of a function it uses. Definitions inside a
+/// program wrapped in one outer list, as in the example programs, count as top-level.
+/// Errors are left for the second pass, which evaluates every form in order.
+fn declare_functions(exprs: &[Rc<Expr>], env: &mut Environment) {
+    for expr in exprs {
+        match &**expr {
+            Expr::List(xs) if matches!(xs.first().map(|x| &**x), Some(Expr::List(_))) => {
+                declare_functions(xs, env)
+            }
+            Expr::List(xs) if xs.len() > 1 => {
+                if let (Expr::Symbol(op), Expr::Symbol(name)) = (&*xs[0], &*xs[1]) {
+                    if (op == "fn" || op == "defmemo") && !env.contains_key(name) {
+                        let _ = eval(expr.clone(), env);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Lexes and parses the file at the given path and renders the syntax tree of every
+/// top-level expression, one per line.
+pub fn dump_ast(path: &str) -> Result<String, String> {
+    let content = fs::read_to_string(path).map_err(|_| "Unable to open the file!".to_string())?;
+    let tokens = lex(&content).map_err(|error| format!("Lex error: {:?}", error))?;
+    let exprs = parse_program(&tokens).map_err(|error| format!("Parse error: {:?}", error))?;
+    Ok(exprs
+        .iter()
+        .map(|expr| format!("{:?}", expr))
+        .collect::<Vec<String>>()
+        .join("\n"))
 }
@@ -1,15 +1,29 @@
+use std::fmt;
+
 #[derive(Debug)]
 pub enum Token {
     LPar,
     RPar,
     Literal(String),
+    Str(String),
+    /// ` before an expression, short for quasiquote
+    Quasiquote,
+    /// , before an expression, short for unquote
+    Unquote,
+    /// ,@ before an expression, short for unquote-splicing
+    UnquoteSplicing,
 }
 
 impl PartialEq for Token {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (Token::Literal(l1), Token::Literal(l2)) => l1 == l2,
-            (Token::LPar, Token::LPar) | (Token::RPar, Token::RPar) => true,
+            (Token::Str(s1), Token::Str(s2)) => s1 == s2,
+            (Token::LPar, Token::LPar)
+            | (Token::RPar, Token::RPar)
+            | (Token::Quasiquote, Token::Quasiquote)
+            | (Token::Unquote, Token::Unquote)
+            | (Token::UnquoteSplicing, Token::UnquoteSplicing) => true,
             _ => false,
         }
     }
@@ -18,17 +32,130 @@ impl PartialEq for Token {
 #[derive(Debug)]
 pub enum LexError {
     UnknownToken(String),
+    UnterminatedString,
+}
+
+/// Line and column where a token starts, both counted from 1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Span {
+    pub line: usize,
+    pub col: usize,
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, col {}", self.line, self.col)
+    }
 }
 
 pub fn lex(input: &str) -> Result<Vec<Token>, LexError> {
-    input
-        .replace("(", " ( ")
-        .replace(")", " ) ")
-        .split_ascii_whitespace()
-        .map(|s| match s {
-            "(" => Ok(Token::LPar),
-            ")" => Ok(Token::RPar),
-            _ => Ok(Token::Literal(s.to_string())),
-        })
-        .collect()
+    lex_offsets(input)
+        .map(|(tokens, _)| tokens)
+        .map_err(|(error, _)| error)
+}
+
+/// Lex the input, also returning where each token starts
+/// Errors report where the offending token starts
+pub fn lex_with_spans(input: &str) -> Result<(Vec<Token>, Vec<Span>), (LexError, Span)> {
+    match lex_offsets(input) {
+        Ok((tokens, offsets)) => Ok((tokens, spans(input, &offsets))),
+        Err((error, offset)) => Err((error, spans(input, &[offset])[0])),
+    }
+}
+
+/// Lex the input, also returning the byte offset where each token starts
+fn lex_offsets(input: &str) -> Result<(Vec<Token>, Vec<usize>), (LexError, usize)> {
+    let mut tokens = Vec::new();
+    let mut offsets = Vec::new();
+    let mut literal = String::new();
+    let mut literal_start = 0;
+    let mut chars = input.char_indices();
+
+    while let Some((offset, c)) = chars.next() {
+        let delimiter = c == '('
+            || c == ')'
+            || c == '"'
+            || c == ';'
+            || c == '`'
+            || c == ','
+            || c.is_ascii_whitespace();
+        if delimiter && !literal.is_empty() {
+            tokens.push(Token::Literal(literal.clone()));
+            offsets.push(literal_start);
+            literal.clear();
+        }
+        match c {
+            '(' => tokens.push(Token::LPar),
+            ')' => tokens.push(Token::RPar),
+            '`' => tokens.push(Token::Quasiquote),
+            ',' if chars.clone().next().is_some_and(|(_, c)| c == '@') => {
+                chars.next();
+                tokens.push(Token::UnquoteSplicing)
+            }
+            ',' => tokens.push(Token::Unquote),
+            '"' => match lex_string(&mut chars) {
+                Ok(string) => tokens.push(Token::Str(string)),
+                Err(error) => return Err((error, offset)),
+            },
+            // Comments run to the end of the line
+            ';' => {
+                chars.by_ref().find(|&(_, c)| c == '\n');
+            }
+            c if c.is_ascii_whitespace() => {}
+            c => {
+                if literal.is_empty() {
+                    literal_start = offset;
+                }
+                literal.push(c)
+            }
+        }
+        if tokens.len() > offsets.len() {
+            offsets.push(offset);
+        }
+    }
+
+    if !literal.is_empty() {
+        tokens.push(Token::Literal(literal));
+        offsets.push(literal_start);
+    }
+    Ok((tokens, offsets))
+}
+
+/// Turn increasing byte offsets into the input into lines and columns
+fn spans(input: &str, offsets: &[usize]) -> Vec<Span> {
+    let mut spans = Vec::with_capacity(offsets.len());
+    let mut span = Span { line: 1, col: 1 };
+    let mut offsets = offsets.iter().peekable();
+    for (offset, c) in input.char_indices() {
+        while offsets.next_if(|&&start| start == offset).is_some() {
+            spans.push(span);
+        }
+        if c == '\n' {
+            span.line += 1;
+            span.col = 1;
+        } else {
+            span.col += 1;
+        }
+    }
+    spans
+}
+
+/// Read the rest of a string literal after its opening quote
+/// Supports the escapes \" \\ \n \r and \t
+fn lex_string(chars: &mut std::str::CharIndices) -> Result<String, LexError> {
+    let mut string = String::new();
+    while let Some((_, c)) = chars.next() {
+        match c {
+            '"' => return Ok(string),
+            '\\' => match chars.next().map(|(_, c)| c) {
+                Some('n') => string.push('\n'),
+                Some('r') => string.push('\r'),
+                Some('t') => string.push('\t'),
+                Some(c) => string.push(c),
+                None => break,
+            },
+            c => string.push(c),
+        }
+    }
+    Err(LexError::UnterminatedString)
 }
@@ -1,4 +1,4 @@
-use crate::lex::Token;
+use crate::lex::{Span, Token};
 use crate::types::Expr;
 use std::rc::Rc;
 
@@ -11,21 +11,52 @@ pub enum ParseError {
 #[derive(Debug)]
 pub enum ParseResult {
     Success(usize, Rc<Expr>),
-    Failure(ParseError),
+    /// Index of the offending token and the error
+    Failure(usize, ParseError),
 }
 
 pub fn parse(tokens: &[Token]) -> Result<Rc<Expr>, ParseError> {
     match parser(tokens, 0) {
         ParseResult::Success(_, expr) => Ok(expr),
-        ParseResult::Failure(error) => Err(error),
+        ParseResult::Failure(_, error) => Err(error),
     }
 }
 
+/// Parse every top-level expression in the given tokens
+pub fn parse_program(tokens: &[Token]) -> Result<Vec<Rc<Expr>>, ParseError> {
+    parse_all(tokens).map_err(|(_, error)| error)
+}
+
+/// Parse every top-level expression, reporting where the offending token starts on failure
+/// The spans are those returned by lex_with_spans for the same tokens
+pub fn parse_program_with_spans(
+    tokens: &[Token],
+    spans: &[Span],
+) -> Result<Vec<Rc<Expr>>, (ParseError, Option<Span>)> {
+    parse_all(tokens).map_err(|(index, error)| (error, spans.get(index).copied()))
+}
+
+fn parse_all(tokens: &[Token]) -> Result<Vec<Rc<Expr>>, (usize, ParseError)> {
+    let mut exprs = Vec::new();
+    let mut index = 0;
+    while index < tokens.len() {
+        match parser(tokens, index) {
+            ParseResult::Success(idx, expr) => {
+                index = idx;
+                exprs.push(expr);
+            }
+            ParseResult::Failure(idx, error) => return Err((idx, error)),
+        }
+    }
+    Ok(exprs)
+}
+
 fn parser(tokens: &[Token], index: usize) -> ParseResult {
     let mut index = index;
     if let Some(mut t) = tokens.get(index) {
-        match &*t {
+        match t {
             Token::LPar => {
+                let start = index;
                 index += 1;
                 let mut exprs = Vec::new();
                 while *t != Token::RPar {
@@ -34,29 +65,63 @@ fn parser(tokens: &[Token], index: usize) -> ParseResult {
                             index = idx;
                             exprs.push(expr);
                         }
-                        ParseResult::Failure(error) => return ParseResult::Failure(error),
+                        failure => return failure,
                     }
                     if index >= tokens.len() {
-                        return ParseResult::Failure(ParseError::BadParse(
-                            "Unclosed delimiter!".into(),
-                        ));
+                        return ParseResult::Failure(
+                            start,
+                            ParseError::BadParse("Unclosed delimiter!".into()),
+                        );
                     }
                     t = &tokens[index];
                 }
                 ParseResult::Success(index + 1, Expr::list(&exprs))
             }
-            Token::RPar => {
-                ParseResult::Failure(ParseError::BadParse("Unexpected ) encountered!".into()))
-            }
+            Token::RPar => ParseResult::Failure(
+                index,
+                ParseError::BadParse("Unexpected ) encountered!".into()),
+            ),
+            Token::Quasiquote => parse_prefixed(tokens, index, "quasiquote"),
+            Token::Unquote => parse_prefixed(tokens, index, "unquote"),
+            Token::UnquoteSplicing => parse_prefixed(tokens, index, "unquote-splicing"),
+            Token::Str(s) => ParseResult::Success(index + 1, Expr::string(s)),
             Token::Literal(s) => {
-                if let Ok(n) = &s.parse::<f64>() {
+                if let Ok(n) = s.parse::<i64>() {
+                    ParseResult::Success(index + 1, Expr::int(n))
+                } else if let Ok(n) = &s.parse::<f64>() {
                     ParseResult::Success(index + 1, Expr::fnum(*n))
+                } else if let Some(ratio) = parse_ratio(s) {
+                    ParseResult::Success(index + 1, ratio)
+                } else if s.len() > 1 && s.starts_with(':') {
+                    ParseResult::Success(index + 1, Expr::keyword(&s[1..]))
                 } else {
-                    ParseResult::Success(index + 1, Expr::symbol(&s))
+                    ParseResult::Success(index + 1, Expr::symbol(s))
                 }
             }
         }
     } else {
-        ParseResult::Failure(ParseError::EOF)
+        ParseResult::Failure(index, ParseError::EOF)
+    }
+}
+
+/// Parse the expression after a shorthand token such as ` into its long form (quasiquote expr)
+fn parse_prefixed(tokens: &[Token], index: usize, form: &str) -> ParseResult {
+    match parser(tokens, index + 1) {
+        ParseResult::Success(idx, expr) => {
+            ParseResult::Success(idx, Expr::list(&[Expr::symbol(form), expr]))
+        }
+        ParseResult::Failure(_, ParseError::EOF) => ParseResult::Failure(
+            index,
+            ParseError::BadParse(format!("Missing expression after {}!", form)),
+        ),
+        failure => failure,
     }
 }
+
+/// Parse a fraction literal such as 1/3
+fn parse_ratio(s: &str) -> Option<Rc<Expr>> {
+    let mut parts = s.splitn(2, '/');
+    let n = parts.next()?.parse::<i64>().ok()?;
+    let d = parts.next()?.parse::<i64>().ok()?;
+    Expr::ratio(n, d)
+}
@@ -1,18 +1,64 @@
+use std::fmt;
 use std::rc::Rc;
 
 #[derive(Debug)]
 pub enum Expr {
     Symbol(String),
+    /// Self-evaluating keyword such as :foo, stored without the colon
+    Keyword(String),
+    Str(String),
+    /// Whole number written without a decimal point
+    Int(i64),
     FNum(f64),
+    /// Exact fraction in lowest terms with a denominator greater than one
+    Ratio(i64, i64),
     List(Vec<Rc<Expr>>),
+    /// Anonymous function created by lambda
+    Lambda {
+        params: Vec<String>,
+        body: Rc<Expr>,
+    },
 }
 
 impl PartialEq for Expr {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (Expr::Symbol(s1), Expr::Symbol(s2)) => s1 == s2,
+            (Expr::Keyword(k1), Expr::Keyword(k2)) => k1 == k2,
+            (Expr::Str(s1), Expr::Str(s2)) => s1 == s2,
+            (Expr::Int(n1), Expr::Int(n2)) => n1 == n2,
+            // NaN equals itself so structures containing it compare equal
+            (Expr::FNum(n1), Expr::FNum(n2)) if n1.is_nan() || n2.is_nan() => {
+                n1.is_nan() && n2.is_nan()
+            }
             (Expr::FNum(n1), Expr::FNum(n2)) => (n1 - n2).abs() <= 1e-8,
+            // Whole numbers and fractions compare exactly, only floats use the tolerance
+            (Expr::Int(_) | Expr::Ratio(..), Expr::Int(_) | Expr::Ratio(..)) => {
+                match (self.as_fraction(), other.as_fraction()) {
+                    (Some((n1, d1)), Some((n2, d2))) => {
+                        n1 as i128 * d2 as i128 == n2 as i128 * d1 as i128
+                    }
+                    _ => false,
+                }
+            }
+            (
+                Expr::Int(_) | Expr::Ratio(..) | Expr::FNum(_),
+                Expr::Int(_) | Expr::Ratio(..) | Expr::FNum(_),
+            ) => match (self.as_f64(), other.as_f64()) {
+                (Some(n1), Some(n2)) => (n1 - n2).abs() <= 1e-8,
+                _ => false,
+            },
             (Expr::List(xs1), Expr::List(xs2)) => xs1 == xs2,
+            (
+                Expr::Lambda {
+                    params: p1,
+                    body: b1,
+                },
+                Expr::Lambda {
+                    params: p2,
+                    body: b2,
+                },
+            ) => p1 == p2 && b1 == b2,
             _ => false,
         }
     }
@@ -20,20 +66,184 @@ impl PartialEq for Expr {
 
 impl Eq for Expr {}
 
+/// Nesting depth beyond which rendering shows ... instead of the structure
+pub const MAX_RENDER_DEPTH: usize = 100;
+
+impl fmt::Display for Expr {
+    /// Render the expression as source code, without resolving any symbols
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_expr(f, self, 0, MAX_RENDER_DEPTH)
+    }
+}
+
+/// Write an expression found at the given nesting depth as source code
+fn write_expr(
+    out: &mut impl fmt::Write,
+    expr: &Expr,
+    depth: usize,
+    max_depth: usize,
+) -> fmt::Result {
+    if depth >= max_depth {
+        return write!(out, "...");
+    }
+
+    match expr {
+        Expr::Symbol(s) => write!(out, "{}", s),
+        Expr::Keyword(k) => write!(out, ":{}", k),
+        Expr::Str(s) => write!(out, "{:?}", s),
+        Expr::Int(n) => write!(out, "{}", n),
+        Expr::FNum(n) => write!(out, "{}", n),
+        Expr::Ratio(n, d) => write!(out, "{}/{}", n, d),
+        Expr::List(xs) => {
+            write!(out, "(")?;
+            for (i, x) in xs.iter().enumerate() {
+                if i > 0 {
+                    write!(out, " ")?;
+                }
+                write_expr(out, x, depth + 1, max_depth)?;
+            }
+            write!(out, ")")
+        }
+        Expr::Lambda { params, body } => {
+            write!(out, "(lambda ({}) ", params.join(" "))?;
+            write_expr(out, body, depth + 1, max_depth)?;
+            write!(out, ")")
+        }
+    }
+}
+
 impl Expr {
+    /// Render the expression as source code, showing ... for structure nested max_depth deep
+    pub fn render(&self, max_depth: usize) -> String {
+        let mut output = String::new();
+        let _ = write_expr(&mut output, self, 0, max_depth);
+        output
+    }
+
     pub fn symbol(s: &str) -> Rc<Expr> {
         Rc::new(Expr::Symbol(s.to_string()))
     }
 
+    pub fn keyword(k: &str) -> Rc<Expr> {
+        Rc::new(Expr::Keyword(k.to_string()))
+    }
+
+    pub fn string(s: &str) -> Rc<Expr> {
+        Rc::new(Expr::Str(s.to_string()))
+    }
+
+    pub fn int(n: i64) -> Rc<Expr> {
+        Rc::new(Expr::Int(n))
+    }
+
     pub fn fnum(n: f64) -> Rc<Expr> {
         Rc::new(Expr::FNum(n))
     }
 
     pub fn list(xs: &[Rc<Expr>]) -> Rc<Expr> {
-        Rc::new(Expr::List(xs.iter().cloned().collect()))
+        Rc::new(Expr::List(xs.to_vec()))
+    }
+
+    /// Build an exact fraction in lowest terms, or a number if it is whole
+    pub fn ratio(n: i64, d: i64) -> Option<Rc<Expr>> {
+        if d == 0 {
+            return None;
+        }
+        // The divisor is i64::MIN when both numbers are multiples of 2^63, which flips the signs
+        let g = gcd(n, d);
+        let (n, d) = (n.checked_div(g)?, d.checked_div(g)?);
+        let (n, d) = if d < 0 {
+            (n.checked_neg()?, d.checked_neg()?)
+        } else {
+            (n, d)
+        };
+        if d == 1 {
+            Some(Expr::int(n))
+        } else {
+            Some(Rc::new(Expr::Ratio(n, d)))
+        }
+    }
+
+    /// Get the numerator and denominator of a whole number or fraction
+    pub fn as_fraction(&self) -> Option<(i64, i64)> {
+        match self {
+            Expr::Int(n) => Some((*n, 1)),
+            Expr::Ratio(n, d) => Some((*n, *d)),
+            _ => None,
+        }
+    }
+
+    /// Get the value of a number or fraction as a float
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Expr::Int(n) => Some(*n as f64),
+            Expr::FNum(n) => Some(*n),
+            Expr::Ratio(n, d) => Some(*n as f64 / *d as f64),
+            _ => None,
+        }
+    }
+}
+
+/// Conversion of Rust literals into expressions, used by the risp! macro
+pub trait ToExpr {
+    fn to_expr(self) -> Rc<Expr>;
+}
+
+impl ToExpr for i32 {
+    fn to_expr(self) -> Rc<Expr> {
+        Expr::int(self as i64)
     }
 }
 
+impl ToExpr for i64 {
+    fn to_expr(self) -> Rc<Expr> {
+        Expr::int(self)
+    }
+}
+
+impl ToExpr for f64 {
+    fn to_expr(self) -> Rc<Expr> {
+        Expr::fnum(self)
+    }
+}
+
+impl ToExpr for &str {
+    fn to_expr(self) -> Rc<Expr> {
+        Expr::string(self)
+    }
+}
+
+/// Build an expression from Risp-like Rust tokens
+/// risp!((+ 1 (* 2 3)))
+/// Names must be Rust identifiers or single operators, and negative numbers need braces
+/// risp!((let x {-1}))
+#[macro_export]
+macro_rules! risp {
+    (($($x:tt)*)) => {
+        $crate::types::Expr::list(&[$($crate::risp!($x)),*])
+    };
+    ({$x:expr}) => {
+        $crate::types::ToExpr::to_expr($x)
+    };
+    ($x:literal) => {
+        $crate::types::ToExpr::to_expr($x)
+    };
+    ($x:tt) => {
+        $crate::types::Expr::symbol(stringify!($x))
+    };
+}
+
+/// Greatest common divisor, always positive for a non-zero b
+fn gcd(a: i64, b: i64) -> i64 {
+    let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+    while b != 0 {
+        let t = a % b;
+        a = b;
+        b = t;
+    }
+    a as i64
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -41,21 +251,52 @@ mod test {
     #[test]
     fn build_symbol() {
         let sym_str = "a";
-        let sym = Expr::symbol(&sym_str);
+        let sym = Expr::symbol(sym_str);
         match &*sym {
             Expr::Symbol(s) => assert_eq!(sym_str, s),
-            _ => assert!(false),
+            _ => panic!(),
         }
     }
 
+    #[test]
+    fn build_keyword() {
+        let kw = Expr::keyword("foo");
+        match &*kw {
+            Expr::Keyword(k) => assert_eq!("foo", k),
+            _ => panic!(),
+        }
+        assert_ne!(Expr::keyword("foo"), Expr::symbol("foo"));
+    }
+
+    #[test]
+    fn build_string() {
+        let string = Expr::string("hello world");
+        match &*string {
+            Expr::Str(s) => assert_eq!("hello world", s),
+            _ => panic!(),
+        }
+        assert_ne!(Expr::string("a"), Expr::symbol("a"));
+    }
+
     #[test]
     fn build_fnum() {
         let val = 1.0;
         let fnum = Expr::fnum(val);
         match &*fnum {
             Expr::FNum(n) => assert!((n - val).abs() <= 1e-8),
-            _ => assert!(false),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn build_int() {
+        match &*Expr::int(3) {
+            Expr::Int(n) => assert_eq!(3, *n),
+            _ => panic!(),
         }
+        assert_eq!(Expr::Int(3), Expr::FNum(3.0));
+        assert_ne!(Expr::Int(3), Expr::FNum(3.5));
+        assert_eq!(Some(Expr::int(2)), Expr::ratio(4, 2));
     }
 
     #[test]
@@ -64,17 +305,91 @@ mod test {
         assert_eq!(Expr::FNum(0.99), Expr::FNum(0.99));
     }
 
+    #[test]
+    fn check_nan_equality() {
+        assert_eq!(Expr::FNum(f64::NAN), Expr::FNum(f64::NAN));
+        assert_ne!(Expr::FNum(f64::NAN), Expr::FNum(1.0));
+        assert_ne!(Expr::FNum(1.0), Expr::FNum(2.0));
+
+        let xs = Expr::list(&[Expr::fnum(1.0), Expr::fnum(f64::NAN)]);
+        let ys = Expr::list(&[Expr::fnum(1.0), Expr::fnum(f64::NAN)]);
+        assert_eq!(xs, xs.clone());
+        assert_eq!(xs, ys);
+    }
+
+    #[test]
+    fn build_ratio() {
+        assert_eq!(Some(Rc::new(Expr::Ratio(1, 2))), Expr::ratio(2, 4));
+        assert_eq!(Some(Rc::new(Expr::Ratio(-1, 3))), Expr::ratio(1, -3));
+        assert_eq!(Some(Expr::int(2)), Expr::ratio(4, 2));
+        assert_eq!(None, Expr::ratio(1, 0));
+        assert_eq!(Some(Expr::int(1)), Expr::ratio(i64::MIN, i64::MIN));
+        assert_eq!(Some(Expr::int(0)), Expr::ratio(0, i64::MIN));
+        assert_eq!(
+            Some(Rc::new(Expr::Ratio(1, 2))),
+            Expr::ratio(i64::MIN / 2, i64::MIN)
+        );
+        assert_eq!(None, Expr::ratio(i64::MIN, -1));
+        assert_eq!(Expr::Ratio(1, 2), Expr::FNum(0.5));
+        assert_ne!(Expr::Ratio(1, 1_000_000_000), Expr::Int(0));
+        assert_ne!(Expr::Int(i64::MAX), Expr::Int(i64::MAX - 1));
+        assert_ne!(Expr::Ratio(1, i64::MAX), Expr::Ratio(1, i64::MAX - 1));
+    }
+
+    #[test]
+    fn display_renders_source() {
+        let expr = crate::risp!((fn square (x) (* x x 2.5 {-3}) "a b"));
+        assert_eq!("(fn square (x) (* x x 2.5 -3) \"a b\")", expr.to_string());
+        assert_eq!(":k", Expr::keyword("k").to_string());
+        assert_eq!("2", Expr::FNum(2.0).to_string());
+        assert_eq!("1/3", Expr::Ratio(1, 3).to_string());
+        assert_eq!("()", Expr::list(&[]).to_string());
+
+        let lambda = Expr::Lambda {
+            params: vec!["x".into(), "y".into()],
+            body: crate::risp!((+ x y)),
+        };
+        assert_eq!("(lambda (x y) (+ x y))", format!("{}", lambda));
+    }
+
+    #[test]
+    fn build_with_macro() {
+        let expected = Expr::list(&[
+            Expr::symbol("+"),
+            Expr::int(1),
+            Expr::list(&[Expr::symbol("*"), Expr::fnum(2.5), Expr::int(-3)]),
+            Expr::string("a"),
+            Expr::symbol("x"),
+        ]);
+        assert_eq!(expected, crate::risp!((+ 1 (* 2.5 {-3}) "a" x)));
+        assert_eq!(Expr::list(&[]), crate::risp!(()));
+    }
+
     #[test]
     fn build_list() {
-        let vals = Expr::list(&[Expr::symbol("+"), Expr::fnum(1.0), Expr::fnum(1.0)]);
+        let vals = Expr::list(&[Expr::symbol("+"), Expr::int(1), Expr::int(1)]);
         match &*vals {
             Expr::List(xs) => {
                 assert_eq!(xs.len(), 3);
                 assert_eq!(Expr::symbol("+"), xs[0]);
-                assert_eq!(Expr::fnum(1.0), xs[1]);
-                assert_eq!(Expr::fnum(1.0), xs[2]);
+                assert_eq!(Expr::int(1), xs[1]);
+                assert_eq!(Expr::int(1), xs[2]);
             }
-            _ => assert!(false),
+            _ => panic!(),
         }
     }
+
+    #[test]
+    fn display_caps_nesting_depth() {
+        let nested = Expr::list(&[Expr::list(&[
+            Expr::symbol("a"),
+            Expr::list(&[Expr::int(1)]),
+        ])]);
+        assert_eq!("((a (1)))", nested.to_string());
+        assert_eq!("((a (...)))", nested.render(3));
+
+        let deep = (0..MAX_RENDER_DEPTH).fold(Expr::int(1), |expr, _| Expr::list(&[expr]));
+        let shown = deep.to_string();
+        assert!(shown.ends_with(&format!("(...{}", ")".repeat(MAX_RENDER_DEPTH))));
+    }
 }
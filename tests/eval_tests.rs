@@ -1,7 +1,11 @@
 #[cfg(test)]
 mod eval_tests {
-    use rust_lisp::eval::{eval, gen_print_output, Environment, EvalResult};
+    use rust_lisp::eval::{
+        eval, gen_dbg_output, gen_inspect_output, gen_print_output, gen_spy_output, Environment,
+        EvalResult,
+    };
     use rust_lisp::types::Expr;
+    use std::rc::Rc;
 
     #[test]
     fn add_and_check_simple_var_in_env() {
@@ -142,6 +146,34 @@ mod eval_tests {
         assert_eq!(EvalResult::Expr(expected), r);
     }
 
+    #[test]
+    fn arithmetic_op_add_identity() {
+        let expr = Expr::list(&[Expr::symbol("+")]);
+        let mut env = Environment::empty();
+        let r = eval(expr, &mut env);
+        assert_eq!(EvalResult::Expr(Expr::int(0)), r);
+    }
+
+    #[test]
+    fn arithmetic_op_mul_identity() {
+        let expr = Expr::list(&[Expr::symbol("*")]);
+        let mut env = Environment::empty();
+        let r = eval(expr, &mut env);
+        assert_eq!(EvalResult::Expr(Expr::int(1)), r);
+    }
+
+    #[test]
+    fn arithmetic_op_sub_div_without_args_fail() {
+        let mut env = Environment::empty();
+        for op in &["-", "/"] {
+            let expr = Expr::list(&[Expr::symbol(op)]);
+            match eval(expr, &mut env) {
+                EvalResult::Err(_) => {}
+                r => panic!("Expected EvalResult::Err for ({}), got {:?}", op, r),
+            }
+        }
+    }
+
     #[test]
     fn equality_works_1() {
         let expr = Expr::list(&[
@@ -313,6 +345,35 @@ mod eval_tests {
         assert_eq!(EvalResult::Expr(expected), r);
     }
 
+    #[test]
+    fn boolean_op_and_numbers_are_truthy() {
+        let expr = Expr::list(&[Expr::symbol("and"), Expr::fnum(1.0), Expr::fnum(2.0)]);
+        let expected = Expr::symbol("True");
+        let mut env = Environment::default();
+        let r = eval(expr, &mut env);
+        assert_eq!(EvalResult::Expr(expected), r);
+    }
+
+    #[test]
+    fn boolean_op_error_messages() {
+        let mut env = Environment::default();
+
+        let empty = Expr::list(&[Expr::symbol("or")]);
+        assert_eq!(
+            EvalResult::Err("Boolean operations must be performed on at least one value!".into()),
+            eval(empty, &mut env)
+        );
+
+        let unit = Expr::list(&[
+            Expr::symbol("and"),
+            Expr::list(&[Expr::symbol("let"), Expr::symbol("x"), Expr::fnum(1.0)]),
+        ]);
+        assert_eq!(
+            EvalResult::Err("Boolean operations must be performed on values!".into()),
+            eval(unit, &mut env)
+        );
+    }
+
     #[test]
     fn boolean_not_works_1() {
         let expr = Expr::list(&[Expr::symbol("not"), Expr::symbol("True")]);
@@ -342,18 +403,145 @@ mod eval_tests {
         let r = eval(expr, &mut env);
         assert_eq!(EvalResult::Expr(expected), r);
     }
+
+    #[test]
+    fn symbol_append_works() {
+        let expr = Expr::list(&[
+            Expr::symbol("symbol-append"),
+            Expr::symbol("get-"),
+            Expr::symbol("x"),
+        ]);
+        let mut env = Environment::default();
+        let r = eval(expr, &mut env);
+        assert_eq!(EvalResult::Expr(Expr::symbol("get-x")), r);
+    }
+
+    #[test]
+    fn symbol_append_mixes_symbols_and_strings() {
+        let expr = Expr::list(&[
+            Expr::symbol("symbol-append"),
+            Expr::list(&[Expr::symbol("quasiquote"), Expr::symbol("get-")]),
+            Expr::string("x"),
+        ]);
+        let mut env = Environment::default();
+        let r = eval(expr, &mut env);
+        assert_eq!(EvalResult::Expr(Expr::symbol("get-x")), r);
+    }
+
+    #[test]
+    fn symbol_append_rejects_non_symbols() {
+        let expr = Expr::list(&[
+            Expr::symbol("symbol-append"),
+            Expr::symbol("get-"),
+            Expr::fnum(1.0),
+        ]);
+        let mut env = Environment::default();
+        match eval(expr, &mut env) {
+            EvalResult::Err(_) => {}
+            r => panic!("Expected EvalResult::Err, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn symbol_append_rejects_keyword_mixed_with_string() {
+        let expr = Expr::list(&[
+            Expr::symbol("symbol-append"),
+            Expr::string("get-"),
+            Expr::keyword("x"),
+        ]);
+        let mut env = Environment::default();
+        match eval(expr, &mut env) {
+            EvalResult::Err(error) => assert_eq!(
+                "Symbol concatenation must be performed on symbols or strings!",
+                error
+            ),
+            r => panic!("Expected EvalResult::Err, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn current_time_ms_is_positive() {
+        let expr = Expr::list(&[Expr::symbol("current-time-ms")]);
+        let mut env = Environment::default();
+        match eval(expr, &mut env) {
+            EvalResult::Expr(e) => match *e {
+                Expr::FNum(n) => assert!(n > 0.0, "Expected a positive time, got {}", n),
+                _ => panic!("Expected FNum, got {:?}", e),
+            },
+            r => panic!("Expected EvalResult::Expr, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn elapsed_is_non_negative() {
+        let expr = Expr::list(&[
+            Expr::symbol("elapsed"),
+            Expr::list(&[Expr::symbol("current-time-ms")]),
+        ]);
+        let mut env = Environment::default();
+        match eval(expr, &mut env) {
+            EvalResult::Expr(e) => match *e {
+                Expr::FNum(n) => assert!(n >= 0.0, "Expected a non-negative time, got {}", n),
+                _ => panic!("Expected FNum, got {:?}", e),
+            },
+            r => panic!("Expected EvalResult::Expr, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn inspect_returns_value() {
+        let xs = Expr::list(&[Expr::fnum(1.0), Expr::symbol("a")]);
+        let expr = Expr::list(&[Expr::symbol("inspect"), xs.clone()]);
+        let mut env = Environment::default();
+        assert_eq!(EvalResult::Expr(xs), eval(expr, &mut env));
+    }
+
+    #[test]
+    fn inspect_describes_structure() {
+        let xs = Expr::list(&[Expr::fnum(1.0), Expr::symbol("a")]);
+        assert_eq!(
+            "list, length 2\n  0: number\n  1: symbol",
+            gen_inspect_output(&xs)
+        );
+        assert_eq!("number", gen_inspect_output(&Expr::FNum(1.0)));
+    }
+
+    #[test]
+    fn compare_works() {
+        let cases = [
+            (Expr::fnum(1.0), Expr::fnum(2.0), -1),
+            (Expr::fnum(3.0), Expr::fnum(3.0), 0),
+            (Expr::symbol("b"), Expr::symbol("a"), 1),
+        ];
+        let mut env = Environment::default();
+        for (a, b, expected) in cases.iter() {
+            let expr = Expr::list(&[Expr::symbol("compare"), a.clone(), b.clone()]);
+            assert_eq!(EvalResult::Expr(Expr::int(*expected)), eval(expr, &mut env));
+        }
+    }
+
+    #[test]
+    fn compare_type_mismatch_fails() {
+        let expr = Expr::list(&[Expr::symbol("compare"), Expr::fnum(1.0), Expr::symbol("a")]);
+        let mut env = Environment::default();
+        match eval(expr, &mut env) {
+            EvalResult::Err(_) => {}
+            r => panic!("Expected EvalResult::Err, got {:?}", r),
+        }
+    }
+
     #[test]
     fn add_fn_to_env() {
         let x1_sym = "x1";
         let x2_sym = "x2";
-        let x1 = Expr::symbol(&x1_sym);
-        let x2 = Expr::symbol(&x2_sym);
+        let x1 = Expr::symbol(x1_sym);
+        let x2 = Expr::symbol(x2_sym);
         let params = Expr::list(&[x1.clone(), x2.clone()]);
         let fn_body = Expr::list(&[Expr::symbol("+"), x1.clone(), x2.clone()]);
         let f_name = "test-func";
         let expr = Expr::list(&[
             Expr::symbol("fn"),
-            Expr::symbol(&f_name),
+            Expr::symbol(f_name),
             params.clone(),
             fn_body.clone(),
         ]);
@@ -363,8 +551,8 @@ mod eval_tests {
         let r = eval(expr, &mut env);
         assert_eq!(r, EvalResult::Unit);
 
-        env.lookup(&f_name).map_or_else(
-            || assert!(false, "Expected function in environment but got None"),
+        env.lookup(f_name).map_or_else(
+            || panic!("Expected function in environment but got None"),
             |(params, body)| {
                 assert_eq!(&params[0], x1_sym);
                 assert_eq!(&params[1], x2_sym);
@@ -406,10 +594,10 @@ mod eval_tests {
             if let Expr::FNum(n) = *e {
                 assert_eq!(n, x + y + 1.0);
             } else {
-                assert!(false, format!("Expected FNum(6.0), got {:?}", e));
+                panic!("Expected FNum(6.0), got {:?}", e);
             }
         } else {
-            assert!(false, format!("Expected Expr::fnum(6.0), got {:?}", r2));
+            panic!("Expected Expr::fnum(6.0), got {:?}", r2);
         }
     }
 
@@ -425,9 +613,9 @@ mod eval_tests {
         let mut env = Environment::default();
         let result = eval(e, &mut env);
         if let EvalResult::Expr(expr) = result {
-            assert_eq!(Expr::list(&[Expr::symbol("x".into())]), expr);
+            assert_eq!(Expr::list(&[Expr::symbol("x")]), expr);
         } else {
-            assert!(false, "Expected expression, got {:?}", result);
+            panic!("Expected expression, got {:?}", result);
         }
     }
 
@@ -442,9 +630,9 @@ mod eval_tests {
         let mut env = Environment::default();
         let result = eval(e.clone(), &mut env);
         if let EvalResult::Expr(expr) = result {
-            assert_eq!(Expr::list(&[Expr::symbol("x".into())]), expr);
+            assert_eq!(Expr::list(&[Expr::symbol("x")]), expr);
         } else {
-            assert!(false, "Expected expression, got {:?}", result);
+            panic!("Expected expression, got {:?}", result);
         }
     }
 
@@ -460,9 +648,100 @@ mod eval_tests {
         let mut env = Environment::default();
         let result = eval(e.clone(), &mut env);
         if let EvalResult::Expr(expr) = result {
-            assert_eq!(Expr::list(&[Expr::symbol("y".into())]), expr);
+            assert_eq!(Expr::list(&[Expr::symbol("y")]), expr);
         } else {
-            assert!(false, "Expected expression, got {:?}", result);
+            panic!("Expected expression, got {:?}", result);
+        }
+    }
+
+    #[test]
+    fn test_ite_without_else() {
+        let mut env = Environment::default();
+
+        let e = Expr::list(&[Expr::symbol("if"), Expr::symbol("False"), Expr::fnum(1.0)]);
+        assert_eq!(EvalResult::Unit, eval(e, &mut env));
+
+        let e = Expr::list(&[Expr::symbol("if"), Expr::symbol("True"), Expr::fnum(1.0)]);
+        assert_eq!(EvalResult::Expr(Expr::fnum(1.0)), eval(e, &mut env));
+    }
+
+    #[test]
+    fn spy_output_is_labelled() {
+        let mut env = Environment::empty();
+        assert_eq!(
+            "mid: 6",
+            gen_spy_output(Expr::symbol("mid"), Expr::fnum(6.0), &mut env)
+        );
+    }
+
+    #[test]
+    fn dbg_returns_value() {
+        let source = Expr::list(&[Expr::symbol("+"), Expr::int(1), Expr::int(2)]);
+        let expr = Expr::list(&[Expr::symbol("dbg"), source.clone()]);
+        let mut env = Environment::default();
+        assert_eq!(EvalResult::Expr(Expr::int(3)), eval(expr, &mut env));
+        assert_eq!("(+ 1 2) = 3", gen_dbg_output(&source, &Expr::Int(3), &env));
+        assert_eq!(
+            "s = \"hi\"",
+            gen_dbg_output(&Expr::Symbol("s".into()), &Expr::Str("hi".into()), &env)
+        );
+
+        env.max_print_depth = 2;
+        let nested = Expr::list(&[Expr::list(&[Expr::list(&[Expr::int(1)])])]);
+        assert_eq!(
+            "x = ((...))",
+            gen_dbg_output(&Expr::Symbol("x".into()), &nested, &env)
+        );
+    }
+
+    #[test]
+    fn print_caps_nesting_depth() {
+        let nested = Expr::list(&[Expr::list(&[Expr::list(&[Expr::fnum(1.0)])])]);
+
+        let mut env = Environment::empty();
+        assert_eq!("(((1)))", gen_print_output(nested.clone(), &mut env));
+
+        env.max_print_depth = 2;
+        assert_eq!("((...))", gen_print_output(nested.clone(), &mut env));
+    }
+
+    #[test]
+    fn print_depth_does_not_count_variable_lookups() {
+        let nested = Expr::list(&[Expr::list(&[Expr::int(1)])]);
+        let mut env = Environment::from_vars(&[("b", nested.clone()), ("a", Expr::symbol("b"))]);
+        env.max_print_depth = 3;
+        assert_eq!("((1))", gen_print_output(nested, &mut env));
+        assert_eq!("((1))", gen_print_output(Expr::symbol("b"), &mut env));
+        assert_eq!("((1))", gen_print_output(Expr::symbol("a"), &mut env));
+    }
+
+    #[test]
+    fn print_stops_on_self_referential_variable() {
+        let mut env = Environment::from_vars(&[("a", Expr::symbol("a"))]);
+        env.max_print_depth = 3;
+        assert_eq!("...", gen_print_output(Expr::symbol("a"), &mut env));
+    }
+
+    #[test]
+    fn print_renders_callables_uniformly() {
+        let mut env = Environment::default();
+        assert_eq!("<function>", gen_print_output(Expr::symbol("+"), &mut env));
+
+        let lambda = Rc::new(Expr::Lambda {
+            params: vec!["x".into()],
+            body: Expr::symbol("x"),
+        });
+        let _ = env.add_var("identity", lambda.clone());
+        assert_eq!(
+            "<lambda>",
+            gen_print_output(Expr::symbol("identity"), &mut env)
+        );
+        assert_eq!("<lambda>", gen_print_output(lambda, &mut env));
+
+        let expr = Expr::list(&[Expr::symbol("list"), Expr::symbol("+"), Expr::int(1)]);
+        match eval(expr, &mut env) {
+            EvalResult::Expr(xs) => assert_eq!("(<function> 1)", gen_print_output(xs, &mut env)),
+            r => panic!("Expected EvalResult::Expr, got {:?}", r),
         }
     }
 
@@ -476,6 +755,16 @@ mod eval_tests {
 
         assert_eq!("hello", gen_print_output(e1.clone(), &mut env));
         assert_eq!("3.2", gen_print_output(e2.clone(), &mut env));
+        assert_eq!("-7", gen_print_output(Expr::int(-7), &mut env));
+        assert_eq!(":foo", gen_print_output(Expr::keyword("foo"), &mut env));
+        assert_eq!(
+            "hi there",
+            gen_print_output(Expr::string("hi there"), &mut env)
+        );
+        assert_eq!(
+            "1/3",
+            gen_print_output(Rc::new(Expr::Ratio(1, 3)), &mut env)
+        );
         assert_eq!("(hello world)", gen_print_output(e3.clone(), &mut env));
 
         env.push_context();
@@ -485,21 +774,21 @@ mod eval_tests {
             Expr::symbol("body"),
         )
         .map_or_else(
-            |e| assert!(false, format!("got error {}", e)),
+            |e| panic!("got error {}", e),
             |_| {
                 assert_eq!(
-                    "<func-object: test-func>",
+                    "<function>",
                     gen_print_output(Expr::symbol("test-func"), &mut env)
                 )
             },
         );
         let _ = env
             .add_var("x", Expr::fnum(42.0))
-            .map_err(|e| assert!(false, format!("got error {}", e)));
+            .map_err(|e| panic!("got error {}", e));
 
         let e4 = Expr::list(&[Expr::symbol("test-func"), Expr::symbol("x"), e3.clone()]);
         assert_eq!(
-            "(<func-object: test-func> 42 (hello world))",
+            "(<function> 42 (hello world))",
             gen_print_output(e4.clone(), &mut env)
         );
 
@@ -524,7 +813,7 @@ mod eval_tests {
 
 #[cfg(test)]
 mod environment_tests {
-    use rust_lisp::eval::Environment;
+    use rust_lisp::eval::{eval, Environment, EvalResult};
     use rust_lisp::types::Expr;
     use std::collections::HashMap;
     use std::rc::Rc;
@@ -536,7 +825,8 @@ mod environment_tests {
         let r = env.add_var("a", Expr::fnum(1.0));
         assert!(
             r.is_err(),
-            format!("Expected add_var to fail, but it succeeded: {:?}", r)
+            "Expected add_var to fail, but it succeeded: {:?}",
+            r
         );
     }
 
@@ -561,17 +851,17 @@ mod environment_tests {
     fn default_environment_is_correct() {
         let env = Environment::default();
         env.lookup("False").map_or_else(
-            || assert!(false, "Expected Some, got None"),
+            || panic!("Expected Some, got None"),
             |(ps, expr)| {
                 assert_eq!(0, ps.len());
                 assert_eq!(Expr::list(&[]), expr);
             },
         );
         env.lookup("True").map_or_else(
-            || assert!(false, "Expected Some, got None"),
+            || panic!("Expected Some, got None"),
             |(ps, expr)| {
                 assert_eq!(0, ps.len());
-                assert_eq!(Expr::list(&[Expr::fnum(1.0)]), expr);
+                assert_eq!(Expr::list(&[Expr::int(1)]), expr);
             },
         );
     }
@@ -584,9 +874,10 @@ mod environment_tests {
             .collect::<HashMap<String, (Vec<String>, Rc<Expr>)>>();
         let env = Environment {
             contexts: vec![ctx],
+            ..Environment::empty()
         };
         env.lookup("x").map_or_else(
-            || assert!(false, "Expected Some, got None"),
+            || panic!("Expected Some, got None"),
             |(ps, expr)| {
                 assert_eq!(0, ps.len());
                 assert_eq!(Expr::fnum(1.0), expr);
@@ -603,6 +894,7 @@ mod environment_tests {
             .collect::<HashMap<String, (Vec<String>, Rc<Expr>)>>();
         let env = Environment {
             contexts: vec![ctx],
+            ..Environment::empty()
         };
         assert!(env.contains_key("x"), "Environment should contain x.");
         assert!(
@@ -625,7 +917,7 @@ mod environment_tests {
 
         // Lookup the variable and validate
         env.lookup("a").map_or_else(
-            || assert!(false, "Failed to find var in environment."),
+            || panic!("Failed to find var in environment."),
             |(args, x)| {
                 assert_eq!(val, x);
                 assert_eq!(0usize, args.len());
@@ -634,8 +926,9 @@ mod environment_tests {
 
         // Pop context and check variable is gone
         env.pop_context();
-        env.lookup("a")
-            .map(|x| assert!(false, format!("Expected Err, got {:?}", x)));
+        if let Some(x) = env.lookup("a") {
+            panic!("Expected Err, got {:?}", x);
+        }
         assert_eq!(0usize, env.num_contexts());
     }
 
@@ -643,8 +936,8 @@ mod environment_tests {
     fn add_fn_to_context_works() {
         let x1_sym = "x1";
         let x2_sym = "x2";
-        let x1 = Expr::symbol(&x1_sym);
-        let x2 = Expr::symbol(&x2_sym);
+        let x1 = Expr::symbol(x1_sym);
+        let x2 = Expr::symbol(x2_sym);
         let fn_body = Expr::list(&[Expr::symbol("+"), x1.clone(), x2.clone()]);
         let f_name = "test-func";
 
@@ -652,13 +945,13 @@ mod environment_tests {
         env.push_context();
         assert_eq!(1, env.contexts.len());
         let _ = env.add_fn(
-            &f_name,
+            f_name,
             &[x1_sym.to_string(), x2_sym.to_string()],
             fn_body.clone(),
         );
 
-        let _ = env.lookup(&f_name).map_or_else(
-            || assert!(false, "Expected function in environment but got None"),
+        env.lookup(f_name).map_or_else(
+            || panic!("Expected function in environment but got None"),
             |(params, body)| {
                 assert_eq!(&params[0], x1_sym);
                 assert_eq!(&params[1], x2_sym);
@@ -666,4 +959,46 @@ mod environment_tests {
             },
         );
     }
+
+    #[test]
+    fn completions_include_bindings_and_builtins() {
+        let mut env = Environment::default();
+        let def = Expr::list(&[Expr::symbol("let"), Expr::symbol("foobar"), Expr::fnum(1.0)]);
+        assert_eq!(EvalResult::Unit, eval(def, &mut env));
+
+        assert_eq!(vec!["foobar".to_string()], env.completions("foo"));
+        assert!(env
+            .completions("sym")
+            .contains(&"symbol-append".to_string()));
+        assert!(env.completions("T").contains(&"True".to_string()));
+        assert!(env.completions("zzz").is_empty());
+    }
+
+    #[test]
+    fn shadow_warning_works() {
+        let mut env = Environment::default();
+        let _ = env.add_var("x", Expr::fnum(1.0));
+        env.push_context();
+        assert_eq!(None, env.shadow_warning("x"));
+
+        env.warn_shadowing = true;
+        assert_eq!(
+            Some("Warning: x shadows a binding in an outer context!".to_string()),
+            env.shadow_warning("x")
+        );
+        assert_eq!(None, env.shadow_warning("y"));
+    }
+
+    #[test]
+    fn fold_case_works() {
+        let mut env = Environment::default();
+        let _ = env.add_var("Foo", Expr::fnum(1.0));
+        assert!(env.lookup("foo").is_none());
+
+        env.set_fold_case(true);
+        let _ = env.add_var("Bar", Expr::fnum(2.0));
+        assert_eq!(Some((Vec::new(), Expr::fnum(1.0))), env.lookup("foo"));
+        assert_eq!(Some((Vec::new(), Expr::fnum(2.0))), env.lookup("BAR"));
+        assert!(env.contains_key("TRUE"));
+    }
 }
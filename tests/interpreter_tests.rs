@@ -1,14 +1,22 @@
 #[cfg(test)]
 mod interpreter_tests {
-    use rust_lisp::eval::EvalResult;
-    use rust_lisp::interpreter::run_interpreter;
+    use rust_lisp::eval::{eval, Environment, EvalResult};
+    use rust_lisp::interpreter::{
+        dump_ast, is_complete, run_interpreter, run_interpreter_with_env, run_meta_command,
+    };
+    use rust_lisp::lex::lex;
+    use rust_lisp::parse::parse;
     use rust_lisp::types::Expr;
+    use std::cell::Cell;
+    use std::env;
+    use std::fs;
+    use std::rc::Rc;
 
     #[test]
     fn simple_statement() {
         let program = "(+ 1 (- 3 2))";
-        let r = run_interpreter(&program);
-        assert_eq!(EvalResult::Expr(Expr::fnum(2.0)), r);
+        let r = run_interpreter(program);
+        assert_eq!(EvalResult::Expr(Expr::int(2)), r);
     }
 
     #[test]
@@ -18,7 +26,7 @@ mod interpreter_tests {
         (let y (add-1 x))
         (let z (+ x y))
         (= z (+ x y)))";
-        let r = run_interpreter(&program);
+        let r = run_interpreter(program);
         assert_eq!(EvalResult::Expr(Expr::list(&[Expr::symbol("True")])), r);
     }
 
@@ -30,12 +38,1943 @@ mod interpreter_tests {
         (let y (add-1 x))
         (let z (+ x y))
         (= z (+ x y))";
-        match run_interpreter(&program) {
+        match run_interpreter(program) {
             EvalResult::Err(_) => {}
-            _ => assert!(
-                false,
-                "Expected EvalResult::Err resulting from a bad parse."
+            _ => panic!("Expected EvalResult::Err resulting from a bad parse."),
+        }
+    }
+
+    #[test]
+    fn keyword_arguments_bind_by_name() {
+        let program = "((fn make-window (:width :height) (- width height))
+        (make-window :height 24 :width 80))";
+        let r = run_interpreter(program);
+        assert_eq!(EvalResult::Expr(Expr::list(&[Expr::int(56)])), r);
+    }
+
+    #[test]
+    fn missing_keyword_argument_fails() {
+        let program = "((fn make-window (:width :height) (- width height))
+        (make-window :width 80))";
+        match run_interpreter(program) {
+            EvalResult::Err(error) => assert_eq!("Missing keyword argument :height!", error),
+            r => panic!("Expected EvalResult::Err, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn error_reports_call_stack() {
+        let program = "((fn h (x) (+ x (not-a-number)))
+        (fn g (x) (h x))
+        (fn f (x) (g x))
+        (f 1))";
+        match run_interpreter(program) {
+            EvalResult::Err(error) => assert!(
+                error.ends_with("Call stack: f -> g -> h"),
+                "Expected call stack in error, got {}",
+                error
+            ),
+            r => panic!("Expected EvalResult::Err, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn quasiquote_evaluates_only_unquoted_parts() {
+        let program = "((let xs (4 5))
+        (quasiquote (a (unquote (+ 1 2)) (unquote-splicing xs) b)))";
+        let expected = Expr::list(&[
+            Expr::symbol("a"),
+            Expr::int(3),
+            Expr::int(4),
+            Expr::int(5),
+            Expr::symbol("b"),
+        ]);
+        let r = run_interpreter(program);
+        assert_eq!(EvalResult::Expr(Expr::list(&[expected])), r);
+    }
+
+    #[test]
+    fn quasiquote_keeps_nested_lists_literal() {
+        let program = "(quasiquote (1 (+ 1 1) ((unquote (+ 1 1)))))";
+        let expected = Expr::list(&[
+            Expr::int(1),
+            Expr::list(&[Expr::symbol("+"), Expr::int(1), Expr::int(1)]),
+            Expr::list(&[Expr::int(2)]),
+        ]);
+        let r = run_interpreter(program);
+        assert_eq!(EvalResult::Expr(expected), r);
+    }
+
+    #[test]
+    fn quasiquote_shorthand_matches_long_form() {
+        let program = "((let xs (4 5)) `(a ,(+ 1 2) ,@xs b))";
+        let expected = Expr::list(&[
+            Expr::symbol("a"),
+            Expr::int(3),
+            Expr::int(4),
+            Expr::int(5),
+            Expr::symbol("b"),
+        ]);
+        let r = run_interpreter(program);
+        assert_eq!(EvalResult::Expr(Expr::list(&[expected])), r);
+    }
+
+    #[test]
+    fn let_destructures_flat_list() {
+        let program = "((let (a b c) (1 2 3)) (+ a (* b c)))";
+        let r = run_interpreter(program);
+        assert_eq!(EvalResult::Expr(Expr::list(&[Expr::int(7)])), r);
+    }
+
+    #[test]
+    fn let_destructures_nested_list() {
+        let program = "((let (a (b c)) (1 (2 3))) (+ a (* b c)))";
+        let r = run_interpreter(program);
+        assert_eq!(EvalResult::Expr(Expr::list(&[Expr::int(7)])), r);
+    }
+
+    #[test]
+    fn let_destructuring_shape_mismatch_fails() {
+        for program in &["(let (a b) (1 2 3))", "(let (a (b c)) (1 2))"] {
+            match run_interpreter(program) {
+                EvalResult::Err(_) => {}
+                r => panic!("Expected EvalResult::Err for {}, got {:?}", program, r),
+            }
+        }
+    }
+
+    #[test]
+    fn native_function_is_callable() {
+        let mut env = Environment::default();
+        let registered = env.register_native(
+            "double",
+            Box::new(|args| match args {
+                [n] => match **n {
+                    Expr::Int(n) => EvalResult::Expr(Expr::int(n * 2)),
+                    Expr::FNum(n) => EvalResult::Expr(Expr::fnum(n * 2.0)),
+                    _ => EvalResult::Err("double expects a number!".into()),
+                },
+                _ => EvalResult::Err("double expects one argument!".into()),
+            }),
+        );
+        assert_eq!(Ok(()), registered);
+        assert!(env
+            .register_native("print", Box::new(|_| EvalResult::Unit))
+            .is_err());
+
+        let tokens = lex("(double (+ 20 1))").unwrap();
+        let program = parse(&tokens).unwrap();
+        assert_eq!(EvalResult::Expr(Expr::int(42)), eval(program, &mut env));
+    }
+
+    #[test]
+    fn definitions_cannot_take_native_names() {
+        let mut env = Environment::default();
+        let _ = env.register_native("answer", Box::new(|_| EvalResult::Expr(Expr::int(42))));
+
+        for program in [
+            "(fn answer (x) x)",
+            "(let answer 1)",
+            "(defmemo answer (x) x)",
+            "(let (a answer) (1 2))",
+        ]
+        .iter()
+        {
+            match run_interpreter_with_env(program, &mut env) {
+                EvalResult::Err(error) => assert_eq!(
+                    "answer is a native function and cannot be redefined!", error,
+                    "{}",
+                    program
+                ),
+                r => panic!("Expected EvalResult::Err for {}, got {:?}", program, r),
+            }
+        }
+        assert_eq!(
+            EvalResult::Expr(Expr::int(42)),
+            run_interpreter_with_env("(answer)", &mut env)
+        );
+    }
+
+    #[test]
+    fn stale_call_stack_is_not_reported() {
+        let mut env = Environment::default();
+        run_interpreter_with_env("(fn f (x) (+ x nothing))", &mut env);
+
+        let _ = run_meta_command(":type (f 1)", &mut env);
+        assert_eq!(None, env.error_trace);
+        let r = run_interpreter_with_env("(+ 1 nothing)", &mut env);
+        assert_eq!(
+            EvalResult::Err("Mathematical operations must be performed on numbers!".into()),
+            r
+        );
+
+        let tokens = lex("(f 1)").unwrap();
+        let program = parse(&tokens).unwrap();
+        assert!(matches!(eval(program, &mut env), EvalResult::Err(_)));
+        let r = run_interpreter_with_env("(+ 1 nothing)", &mut env);
+        assert_eq!(
+            EvalResult::Err("Mathematical operations must be performed on numbers!".into()),
+            r
+        );
+    }
+
+    #[test]
+    fn dump_ast_renders_parsed_program() {
+        let ast = dump_ast("programs/1.lisp").unwrap();
+        assert!(ast.starts_with("List(["), "Unexpected AST {}", ast);
+        assert!(ast.contains("Symbol(\"let\")"), "Unexpected AST {}", ast);
+        assert!(ast.contains("Int(1)"), "Unexpected AST {}", ast);
+    }
+
+    #[test]
+    fn dump_ast_renders_every_top_level_form() {
+        let path = env::temp_dir().join("rust_lisp_dump_ast_forms.lisp");
+        fs::write(&path, "(let x 1)\n(print x)\n:done").unwrap();
+        let ast = dump_ast(path.to_str().unwrap()).unwrap();
+        let lines = ast.lines().collect::<Vec<&str>>();
+        assert_eq!(
+            vec![
+                "List([Symbol(\"let\"), Symbol(\"x\"), Int(1)])",
+                "List([Symbol(\"print\"), Symbol(\"x\")])",
+                "Keyword(\"done\")",
+            ],
+            lines
+        );
+    }
+
+    #[test]
+    fn dump_ast_reports_parse_error() {
+        let path = env::temp_dir().join("rust_lisp_dump_ast_bad_parse.lisp");
+        fs::write(&path, "((let x 1)").unwrap();
+        match dump_ast(path.to_str().unwrap()) {
+            Err(error) => assert!(error.starts_with("Parse error"), "Got {}", error),
+            Ok(ast) => panic!("Expected a parse error, got {}", ast),
+        }
+    }
+
+    #[test]
+    fn mutually_recursive_functions_resolve() {
+        let program = "((fn is-even (n) (if (= n 0) True (is-odd (- n 1))))
+        (fn is-odd (n) (if (= n 0) False (is-even (- n 1))))
+        (if (is-even 4) even odd)
+        (if (is-even 3) even odd))";
+        let r = run_interpreter(program);
+        let expected = Expr::list(&[Expr::symbol("even"), Expr::symbol("odd")]);
+        assert_eq!(EvalResult::Expr(expected), r);
+    }
+
+    #[test]
+    fn functions_are_declared_before_top_level_forms_run() {
+        let program = "(fn is-even (n) (if (= n 0) True (is-odd (- n 1))))
+        (let four (is-even 4))
+        (fn is-odd (n) (if (= n 0) False (is-even (- n 1))))
+        (if four even odd)";
+        assert_eq!(
+            EvalResult::Expr(Expr::symbol("even")),
+            run_interpreter(program)
+        );
+
+        let wrapped = "((let r (double 2)) (fn double (x) (* x 2)) r)";
+        let expected = Expr::list(&[Expr::int(4)]);
+        assert_eq!(EvalResult::Expr(expected), run_interpreter(wrapped));
+    }
+
+    #[test]
+    fn hoisting_keeps_later_redefinitions_in_order() {
+        let program = "(fn f (x) 1) (let a (f 0)) (fn f (x) 2) (list a (f 0))";
+        let expected = Expr::list(&[Expr::int(1), Expr::int(2)]);
+        assert_eq!(EvalResult::Expr(expected), run_interpreter(program));
+    }
+
+    #[test]
+    fn list_builds_list_of_values() {
+        assert_eq!(EvalResult::Expr(Expr::list(&[])), run_interpreter("(list)"));
+        assert_eq!(
+            EvalResult::Expr(Expr::int(10)),
+            run_interpreter("(car (list 10 20))")
+        );
+
+        let r = run_interpreter("(let x 5) (list x 1 (+ 1 2))");
+        let expected = Expr::list(&[Expr::int(5), Expr::int(1), Expr::int(3)]);
+        assert_eq!(EvalResult::Expr(expected), r);
+    }
+
+    #[test]
+    fn string_lines_works() {
+        let r = run_interpreter(r#"(string-lines "one\ntwo\r\nthree\n")"#);
+        let expected = Expr::list(&[
+            Expr::string("one"),
+            Expr::string("two"),
+            Expr::string("three"),
+        ]);
+        assert_eq!(EvalResult::Expr(expected), r);
+
+        let r = run_interpreter(r#"(string-lines "")"#);
+        assert_eq!(EvalResult::Expr(Expr::list(&[])), r);
+    }
+
+    #[test]
+    fn string_words_works() {
+        let r = run_interpreter(r#"(string-words "  hello   big world ")"#);
+        let expected = Expr::list(&[
+            Expr::string("hello"),
+            Expr::string("big"),
+            Expr::string("world"),
+        ]);
+        assert_eq!(EvalResult::Expr(expected), r);
+
+        let r = run_interpreter(r#"(string-words "")"#);
+        assert_eq!(EvalResult::Expr(Expr::list(&[])), r);
+
+        match run_interpreter("(string-words 5)") {
+            EvalResult::Err(error) => assert_eq!("Expected a string argument!", error),
+            r => panic!("Expected EvalResult::Err, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn string_repeat_and_reverse_work() {
+        let cases = [
+            (r#"(string-repeat "ab" 3)"#, "ababab"),
+            (r#"(string-repeat "x" 0)"#, ""),
+            (r#"(string-reverse "héllo")"#, "olléh"),
+            (r#"(string-reverse "")"#, ""),
+        ];
+        for (program, expected) in cases.iter() {
+            assert_eq!(
+                EvalResult::Expr(Expr::string(expected)),
+                run_interpreter(program),
+                "{}",
+                program
+            );
+        }
+
+        match run_interpreter(r#"(string-repeat "x" -1)"#) {
+            EvalResult::Err(error) => {
+                assert_eq!("Expected a non-negative whole number argument!", error)
+            }
+            r => panic!("Expected EvalResult::Err, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn string_starts_and_ends_with_work() {
+        let cases = [
+            (r#"(string-starts-with? "hello" "he")"#, "True"),
+            (r#"(string-ends-with? "hello" "llo")"#, "True"),
+            (r#"(string-starts-with? "hello" "lo")"#, "False"),
+            (r#"(string-ends-with? "hello" "he")"#, "False"),
+            (r#"(string-starts-with? "hello" "")"#, "True"),
+            (r#"(string-ends-with? "" "")"#, "True"),
+            (r#"(string-starts-with? "he" "hello")"#, "False"),
+            (r#"(string-ends-with? "lo" "hello")"#, "False"),
+        ];
+        for (program, expected) in cases.iter() {
+            assert_eq!(
+                EvalResult::Expr(Expr::symbol(expected)),
+                run_interpreter(program),
+                "{}",
+                program
+            );
+        }
+
+        match run_interpreter(r#"(string-starts-with? "hello" 1)"#) {
+            EvalResult::Err(error) => assert_eq!("Expected a string argument!", error),
+            r => panic!("Expected EvalResult::Err, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn parse_int_and_parse_float_work() {
+        let cases = [
+            (r#"(parse-int "ff" 16)"#, Expr::int(255)),
+            (r#"(parse-int "42")"#, Expr::int(42)),
+            (r#"(parse-int "-101" 2)"#, Expr::int(-5)),
+            (r#"(parse-float "2.75")"#, Expr::fnum(2.75)),
+            (r#"(parse-float "2")"#, Expr::fnum(2.0)),
+        ];
+        for (program, expected) in cases.iter() {
+            assert_eq!(
+                EvalResult::Expr(expected.clone()),
+                run_interpreter(program),
+                "{}",
+                program
+            );
+        }
+    }
+
+    #[test]
+    fn parse_int_and_parse_float_reject_invalid_input() {
+        let cases = [
+            (
+                r#"(parse-int "12a")"#,
+                r#"Cannot parse "12a" as an integer in base 10!"#,
+            ),
+            (r#"(parse-int "ff" 37)"#, "Radix must be between 2 and 36!"),
+            (
+                r#"(parse-float "three")"#,
+                r#"Cannot parse "three" as a float!"#,
+            ),
+            ("(parse-int 12)", "Expected a string argument!"),
+        ];
+        for (program, message) in cases.iter() {
+            match run_interpreter(program) {
+                EvalResult::Err(error) => assert_eq!(*message, error),
+                r => panic!("Expected EvalResult::Err, got {:?}", r),
+            }
+        }
+    }
+
+    #[test]
+    fn concat_joins_lists_and_strings() {
+        let r = run_interpreter("(concat (list 1 2) (list 3) (list))");
+        let expected = Expr::list(&[Expr::int(1), Expr::int(2), Expr::int(3)]);
+        assert_eq!(EvalResult::Expr(expected), r);
+
+        let r = run_interpreter(r#"(concat "ab" "" "c")"#);
+        assert_eq!(EvalResult::Expr(Expr::string("abc")), r);
+
+        assert_eq!(
+            EvalResult::Expr(Expr::list(&[])),
+            run_interpreter("(concat)")
+        );
+    }
+
+    #[test]
+    fn concat_of_mismatched_types_fails() {
+        match run_interpreter(r#"(concat (list 1) "a")"#) {
+            EvalResult::Err(error) => {
+                assert_eq!("Cannot concatenate a string onto a list!", error)
+            }
+            r => panic!("Expected EvalResult::Err, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn map_applies_function_to_each_element() {
+        let r = run_interpreter("(fn double (x) (* x 2)) (map double (list 1 2 3))");
+        let expected = Expr::list(&[Expr::int(2), Expr::int(4), Expr::int(6)]);
+        assert_eq!(EvalResult::Expr(expected), r);
+
+        let r = run_interpreter("(fn double (x) (* x 2)) (map double (list))");
+        assert_eq!(EvalResult::Expr(Expr::list(&[])), r);
+    }
+
+    #[test]
+    fn map_with_bad_arguments_fails() {
+        let cases = [
+            (
+                "(fn add (x y) (+ x y)) (map add (list 1 2))",
+                "Map must be given a function of one argument, got add!",
+            ),
+            (
+                "(map nothing (list 1 2))",
+                "Map must be given a function of one argument, got nothing!",
+            ),
+            (
+                "(fn double (x) (* x 2)) (map double 5)",
+                "Expected a list argument!",
+            ),
+            (
+                "(map 5 (list 1 2))",
+                "Map must be given a function of one argument, got 5!",
+            ),
+        ];
+        for (program, message) in cases.iter() {
+            match run_interpreter(program) {
+                EvalResult::Err(error) => assert_eq!(*message, error),
+                r => panic!("Expected EvalResult::Err, got {:?}", r),
+            }
+        }
+    }
+
+    #[test]
+    fn map_accepts_lambdas() {
+        let programs = [
+            "(map (lambda (x) (* x x)) (list 1 2))",
+            "(let sq (lambda (x) (* x x))) (map sq (list 1 2))",
+        ];
+        for program in programs.iter() {
+            let r = run_interpreter(program);
+            let expected = Expr::list(&[Expr::int(1), Expr::int(4)]);
+            assert_eq!(EvalResult::Expr(expected), r);
+        }
+    }
+
+    #[test]
+    fn map_accepts_builtins() {
+        let r = run_interpreter("(map add1 (list 1 2))");
+        let expected = Expr::list(&[Expr::int(2), Expr::int(3)]);
+        assert_eq!(EvalResult::Expr(expected), r);
+
+        let r = run_interpreter("(map list (list 1 (list 2)))");
+        let expected = Expr::list(&[
+            Expr::list(&[Expr::int(1)]),
+            Expr::list(&[Expr::list(&[Expr::int(2)])]),
+        ]);
+        assert_eq!(EvalResult::Expr(expected), r);
+    }
+
+    #[test]
+    fn flat_map_joins_results() {
+        let r = run_interpreter("(flat-map (lambda (x) (list x x)) (list 1 2))");
+        let expected = Expr::list(&[Expr::int(1), Expr::int(1), Expr::int(2), Expr::int(2)]);
+        assert_eq!(EvalResult::Expr(expected), r);
+
+        let r = run_interpreter("(fn none (x) (list)) (flat-map none (list 1 2))");
+        assert_eq!(EvalResult::Expr(Expr::list(&[])), r);
+    }
+
+    #[test]
+    fn flat_map_of_non_list_result_fails() {
+        match run_interpreter("(flat-map add1 (list 1 2))") {
+            EvalResult::Err(error) => {
+                assert_eq!("add1 must return a list inside flat-map, got 2!", error)
+            }
+            r => panic!("Expected EvalResult::Err, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn unfold_generates_from_seed() {
+        let r = run_interpreter("(unfold (lambda (s) (list s (+ s 1))) 1 (lambda (s) (> s 5)))");
+        let expected = Expr::list(&[
+            Expr::int(1),
+            Expr::int(2),
+            Expr::int(3),
+            Expr::int(4),
+            Expr::int(5),
+        ]);
+        assert_eq!(EvalResult::Expr(expected), r);
+
+        let r = run_interpreter(
+            "(fn step (s) (list (* s s) (- s 1))) (unfold step 3 (lambda (s) (= s 0)))",
+        );
+        let expected = Expr::list(&[Expr::int(9), Expr::int(4), Expr::int(1)]);
+        assert_eq!(EvalResult::Expr(expected), r);
+    }
+
+    #[test]
+    fn unfold_with_bad_step_or_endless_seed_fails() {
+        let cases = [
+            (
+                "(unfold add1 1 (lambda (s) (> s 5)))",
+                "add1 must return (value next-seed) inside unfold, got 2!",
+            ),
+            (
+                "(unfold (lambda (s) (list s s)) 1 (lambda (s) False))",
+                "Unfold did not stop within 100000 steps!",
+            ),
+        ];
+        for (program, message) in cases.iter() {
+            match run_interpreter(program) {
+                EvalResult::Err(error) => assert_eq!(*message, error),
+                r => panic!("Expected EvalResult::Err, got {:?}", r),
+            }
+        }
+    }
+
+    #[test]
+    fn any_of_and_all_of_combine_predicates() {
+        let definitions = "(fn even (x) (= (% x 2) 0)) (let big (lambda (x) (> x 10)))";
+        let cases = [
+            ("((any-of even big) 3)", "no"),
+            ("((any-of even big) 4)", "yes"),
+            ("((any-of even big) 11)", "yes"),
+            ("((all-of even big) 4)", "no"),
+            ("((all-of even big) 12)", "yes"),
+        ];
+        for (call, expected) in cases.iter() {
+            let r = run_interpreter(&format!("{} (if {} yes no)", definitions, call));
+            assert_eq!(EvalResult::Expr(Expr::symbol(expected)), r, "{}", call);
+        }
+
+        let r = run_interpreter(&format!(
+            "{} (let small-even (all-of even (lambda (x) (< x 10)))) (if (small-even 8) yes no)",
+            definitions
+        ));
+        assert_eq!(EvalResult::Expr(Expr::symbol("yes")), r);
+    }
+
+    #[test]
+    fn any_of_with_bad_predicate_fails() {
+        match run_interpreter("(any-of (lambda (x y) x))") {
+            EvalResult::Err(error) => assert_eq!(
+                "any-of must be given predicates of one argument, got (lambda (x y) x)!",
+                error
+            ),
+            r => panic!("Expected EvalResult::Err, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn operators_are_first_class_values() {
+        let fold = "(fn fold (f acc xs) (if (= xs (list)) acc (fold f (f acc (car xs)) (cdr xs))))";
+        let cases = [
+            (format!("{} (fold + 0 (list 1 2 3))", fold), Expr::int(6)),
+            (format!("{} (fold * 1 (list 1 2 3 4))", fold), Expr::int(24)),
+            ("(let add +) (add 1 2)".to_string(), Expr::int(3)),
+            ("((lambda (f) (f 2 3)) *)".to_string(), Expr::int(6)),
+            ("((if (< 1 2) - +) 5 2)".to_string(), Expr::int(3)),
+            (
+                "(fn check (op x y) (if (op x y) yes no)) (check < 1 2)".to_string(),
+                Expr::symbol("yes"),
+            ),
+            (
+                "(map (lambda (x) (* 2 x)) (list 1 2 3))".to_string(),
+                Expr::list(&[Expr::int(2), Expr::int(4), Expr::int(6)]),
+            ),
+        ];
+        for (program, expected) in cases.iter() {
+            assert_eq!(
+                EvalResult::Expr(expected.clone()),
+                run_interpreter(program),
+                "{}",
+                program
+            );
+        }
+    }
+
+    #[test]
+    fn partition_by_splits_runs_of_equal_keys() {
+        let r = run_interpreter("(partition-by (lambda (x) (% x 2)) (list 1 3 2 4 5))");
+        let expected = Expr::list(&[
+            Expr::list(&[Expr::int(1), Expr::int(3)]),
+            Expr::list(&[Expr::int(2), Expr::int(4)]),
+            Expr::list(&[Expr::int(5)]),
+        ]);
+        assert_eq!(EvalResult::Expr(expected), r);
+
+        let r = run_interpreter("(partition-by add1 (list))");
+        assert_eq!(EvalResult::Expr(Expr::list(&[])), r);
+    }
+
+    #[test]
+    fn chunk_while_splits_on_failed_predicate() {
+        let r = run_interpreter("(chunk-while < (list 1 2 3 2 4 1))");
+        let expected = Expr::list(&[
+            Expr::list(&[Expr::int(1), Expr::int(2), Expr::int(3)]),
+            Expr::list(&[Expr::int(2), Expr::int(4)]),
+            Expr::list(&[Expr::int(1)]),
+        ]);
+        assert_eq!(EvalResult::Expr(expected), r);
+
+        let r = run_interpreter("(chunk-while (lambda (a b) (= b (+ a 1))) (list 1 2 4 5 7))");
+        let expected = Expr::list(&[
+            Expr::list(&[Expr::int(1), Expr::int(2)]),
+            Expr::list(&[Expr::int(4), Expr::int(5)]),
+            Expr::list(&[Expr::int(7)]),
+        ]);
+        assert_eq!(EvalResult::Expr(expected), r);
+    }
+
+    #[test]
+    fn partition_by_and_chunk_while_check_arity() {
+        let cases = [
+            (
+                "(partition-by (lambda (a b) a) (list 1))",
+                "Partition-by must be given a function of one argument, got (lambda (a b) a)!",
+            ),
+            (
+                "(fn inc (x) (+ x 1)) (chunk-while inc (list 1))",
+                "Chunk-while must be given a predicate of two arguments, got inc!",
+            ),
+        ];
+        for (program, message) in cases.iter() {
+            match run_interpreter(program) {
+                EvalResult::Err(error) => assert_eq!(*message, error),
+                r => panic!("Expected EvalResult::Err, got {:?}", r),
+            }
+        }
+    }
+
+    #[test]
+    fn tree_map_keeps_shape() {
+        let r = run_interpreter("(tree-map (lambda (x) (* x 2)) (list 1 (list 2 3)))");
+        let expected = Expr::list(&[Expr::int(2), Expr::list(&[Expr::int(4), Expr::int(6)])]);
+        assert_eq!(EvalResult::Expr(expected), r);
+
+        let r = run_interpreter("(tree-map add1 (list 1 2 3))");
+        let expected = Expr::list(&[Expr::int(2), Expr::int(3), Expr::int(4)]);
+        assert_eq!(EvalResult::Expr(expected), r);
+
+        let r = run_interpreter("(tree-map add1 5)");
+        assert_eq!(EvalResult::Expr(Expr::int(6)), r);
+
+        let r = run_interpreter("(tree-map add1 (list (list) (list (list 1))))");
+        let expected = Expr::list(&[Expr::list(&[]), Expr::list(&[Expr::list(&[Expr::int(2)])])]);
+        assert_eq!(EvalResult::Expr(expected), r);
+    }
+
+    #[test]
+    fn iterate_applies_function_repeatedly() {
+        let r = run_interpreter("(iterate (lambda (x) (* x 2)) 1 4)");
+        let expected = Expr::list(&[Expr::int(1), Expr::int(2), Expr::int(4), Expr::int(8)]);
+        assert_eq!(EvalResult::Expr(expected), r);
+
+        let r = run_interpreter("(iterate add1 5 0)");
+        assert_eq!(EvalResult::Expr(Expr::list(&[])), r);
+
+        match run_interpreter("(iterate add1 5 -1)") {
+            EvalResult::Err(error) => {
+                assert_eq!("Expected a non-negative whole number argument!", error)
+            }
+            r => panic!("Expected EvalResult::Err, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn scan_keeps_running_results() {
+        let r = run_interpreter("(scan + 0 (list 1 2 3))");
+        let expected = Expr::list(&[Expr::int(0), Expr::int(1), Expr::int(3), Expr::int(6)]);
+        assert_eq!(EvalResult::Expr(expected), r);
+
+        let r = run_interpreter("(scan + 7 (list))");
+        assert_eq!(EvalResult::Expr(Expr::list(&[Expr::int(7)])), r);
+
+        let r = run_interpreter(
+            "(fn keep-max (a b) (max-list (list a b))) (scan keep-max 0 (list 3 1 4 1 5))",
+        );
+        let expected = Expr::list(&[
+            Expr::int(0),
+            Expr::int(3),
+            Expr::int(3),
+            Expr::int(4),
+            Expr::int(4),
+            Expr::int(5),
+        ]);
+        assert_eq!(EvalResult::Expr(expected), r);
+    }
+
+    #[test]
+    fn scan_accepts_lambdas() {
+        let programs = [
+            "(scan (lambda (a b) (+ a b)) 0 (list 1 2))",
+            "(let add (lambda (a b) (+ a b))) (scan add 0 (list 1 2))",
+        ];
+        for program in programs.iter() {
+            let r = run_interpreter(program);
+            let expected = Expr::list(&[Expr::int(0), Expr::int(1), Expr::int(3)]);
+            assert_eq!(EvalResult::Expr(expected), r);
+        }
+    }
+
+    #[test]
+    fn scan_does_not_evaluate_list_elements_again() {
+        let r = run_interpreter("(scan concat (list) (list (list + 1 2) (list x)))");
+        let expected = Expr::list(&[
+            Expr::list(&[]),
+            Expr::list(&[Expr::symbol("+"), Expr::int(1), Expr::int(2)]),
+            Expr::list(&[
+                Expr::symbol("+"),
+                Expr::int(1),
+                Expr::int(2),
+                Expr::symbol("x"),
+            ]),
+        ]);
+        assert_eq!(EvalResult::Expr(expected), r);
+    }
+
+    #[test]
+    fn quote_returns_expression_unevaluated() {
+        let r = run_interpreter("(quote (+ 1 2))");
+        let expected = Expr::list(&[Expr::symbol("+"), Expr::int(1), Expr::int(2)]);
+        assert_eq!(EvalResult::Expr(expected), r);
+    }
+
+    #[test]
+    fn scan_with_unknown_function_fails() {
+        match run_interpreter("(scan nothing 0 (list 1))") {
+            EvalResult::Err(error) => {
+                assert_eq!("nothing is not a function of 2 arguments!", error)
+            }
+            r => panic!("Expected EvalResult::Err, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn car_cdr_and_cons_work() {
+        assert_eq!(
+            EvalResult::Expr(Expr::int(1)),
+            run_interpreter("(car (1 2 3))")
+        );
+
+        let r = run_interpreter("(cdr (1 2 3))");
+        let expected = Expr::list(&[Expr::int(2), Expr::int(3)]);
+        assert_eq!(EvalResult::Expr(expected), r);
+
+        let r = run_interpreter("(cdr (1))");
+        assert_eq!(EvalResult::Expr(Expr::list(&[])), r);
+
+        let r = run_interpreter("(cons 1 (cdr (5 2 3)))");
+        let expected = Expr::list(&[Expr::int(1), Expr::int(2), Expr::int(3)]);
+        assert_eq!(EvalResult::Expr(expected), r);
+
+        let r = run_interpreter("(cons 1 False)");
+        assert_eq!(EvalResult::Expr(Expr::list(&[Expr::int(1)])), r);
+    }
+
+    #[test]
+    fn car_and_cdr_of_empty_list_fail() {
+        for (program, message) in &[
+            ("(car False)", "Cannot take car of an empty list!"),
+            ("(cdr False)", "Cannot take cdr of an empty list!"),
+        ] {
+            match run_interpreter(program) {
+                EvalResult::Err(error) => assert_eq!(*message, error),
+                r => panic!("Expected EvalResult::Err, got {:?}", r),
+            }
+        }
+    }
+
+    #[test]
+    fn butlast_works() {
+        let r = run_interpreter("(butlast (1 2 3))");
+        let expected = Expr::list(&[Expr::int(1), Expr::int(2)]);
+        assert_eq!(EvalResult::Expr(expected), r);
+
+        let r = run_interpreter("(butlast False)");
+        assert_eq!(EvalResult::Expr(Expr::list(&[])), r);
+
+        let r = run_interpreter("(butlast (1))");
+        assert_eq!(EvalResult::Expr(Expr::list(&[])), r);
+    }
+
+    #[test]
+    fn last_n_works() {
+        let r = run_interpreter("(last-n 2 (1 2 3 4))");
+        let expected = Expr::list(&[Expr::int(3), Expr::int(4)]);
+        assert_eq!(EvalResult::Expr(expected), r);
+
+        let r = run_interpreter("(last-n 10 (1))");
+        assert_eq!(EvalResult::Expr(Expr::list(&[Expr::int(1)])), r);
+
+        match run_interpreter("(last-n -1 (1))") {
+            EvalResult::Err(_) => {}
+            r => panic!("Expected EvalResult::Err, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn split_at_works() {
+        let r = run_interpreter("(split-at 2 (1 2 3 4))");
+        let expected = Expr::list(&[
+            Expr::list(&[Expr::int(1), Expr::int(2)]),
+            Expr::list(&[Expr::int(3), Expr::int(4)]),
+        ]);
+        assert_eq!(EvalResult::Expr(expected), r);
+
+        let r = run_interpreter("(split-at 0 (1 2))");
+        let expected = Expr::list(&[Expr::list(&[]), Expr::list(&[Expr::int(1), Expr::int(2)])]);
+        assert_eq!(EvalResult::Expr(expected), r);
+
+        let r = run_interpreter("(split-at 5 (1))");
+        let expected = Expr::list(&[Expr::list(&[Expr::int(1)]), Expr::list(&[])]);
+        assert_eq!(EvalResult::Expr(expected), r);
+
+        match run_interpreter("(split-at -1 (1))") {
+            EvalResult::Err(_) => {}
+            r => panic!("Expected EvalResult::Err, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn interleave_works() {
+        let r = run_interpreter("(interleave (1 2 3) (a b c))");
+        let expected = Expr::list(&[
+            Expr::int(1),
+            Expr::symbol("a"),
+            Expr::int(2),
+            Expr::symbol("b"),
+            Expr::int(3),
+            Expr::symbol("c"),
+        ]);
+        assert_eq!(EvalResult::Expr(expected), r);
+
+        let r = run_interpreter("(interleave (1 2 3) (a) (4 5))");
+        let expected = Expr::list(&[Expr::int(1), Expr::symbol("a"), Expr::int(4)]);
+        assert_eq!(EvalResult::Expr(expected), r);
+    }
+
+    #[test]
+    fn interpose_works() {
+        let r = run_interpreter("(interpose 0 (1 2 3))");
+        let expected = Expr::list(&[
+            Expr::int(1),
+            Expr::int(0),
+            Expr::int(2),
+            Expr::int(0),
+            Expr::int(3),
+        ]);
+        assert_eq!(EvalResult::Expr(expected), r);
+
+        let r = run_interpreter("(interpose 0 False)");
+        assert_eq!(EvalResult::Expr(Expr::list(&[])), r);
+    }
+
+    #[test]
+    fn abs_and_sign_work() {
+        assert_eq!(EvalResult::Expr(Expr::int(5)), run_interpreter("(abs -5)"));
+        assert_eq!(
+            EvalResult::Expr(Expr::int(-1)),
+            run_interpreter("(sign -5)")
+        );
+        assert_eq!(EvalResult::Expr(Expr::int(0)), run_interpreter("(sign 0)"));
+        assert_eq!(
+            EvalResult::Expr(Expr::fnum(1.0)),
+            run_interpreter("(sign 2.5)")
+        );
+
+        match run_interpreter("(sign (1 2))") {
+            EvalResult::Err(error) => assert_eq!("Expected a number argument!", error),
+            r => panic!("Expected EvalResult::Err, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn abs_sign_and_clamp_keep_integers() {
+        let cases = [
+            ("(abs -5)", 5),
+            ("(sign -5)", -1),
+            ("(sign 0)", 0),
+            ("(clamp 15 0 10)", 10),
+            ("(/ (abs -7) 2)", 3),
+        ];
+        for (program, expected) in cases.iter() {
+            match run_interpreter(program) {
+                EvalResult::Expr(expr) => assert!(
+                    matches!(*expr, Expr::Int(n) if n == *expected),
+                    "Expected Int({}) for {}, got {:?}",
+                    expected,
+                    program,
+                    expr
+                ),
+                r => panic!("Expected EvalResult::Expr for {}, got {:?}", program, r),
+            }
+        }
+
+        assert_eq!(
+            EvalResult::Expr(Rc::new(Expr::Ratio(1, 2))),
+            run_interpreter("(abs -1/2)")
+        );
+        match run_interpreter("(clamp 15 0 10.5)") {
+            EvalResult::Expr(expr) => assert!(matches!(*expr, Expr::FNum(_)), "Got {:?}", expr),
+            r => panic!("Expected EvalResult::Expr, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn clamp_works() {
+        assert_eq!(
+            EvalResult::Expr(Expr::int(10)),
+            run_interpreter("(clamp 15 0 10)")
+        );
+        assert_eq!(
+            EvalResult::Expr(Expr::int(0)),
+            run_interpreter("(clamp -3 0 10)")
+        );
+        assert_eq!(
+            EvalResult::Expr(Expr::int(5)),
+            run_interpreter("(clamp 5 0 10)")
+        );
+
+        match run_interpreter("(clamp 5 10 0)") {
+            EvalResult::Err(error) => {
+                assert_eq!("Lower bound of clamp must not exceed upper bound!", error)
+            }
+            r => panic!("Expected EvalResult::Err, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn keyword_evaluates_to_itself() {
+        assert_eq!(
+            EvalResult::Expr(Expr::keyword("foo")),
+            run_interpreter(":foo")
+        );
+
+        let r = run_interpreter("((let k :foo) k)");
+        assert_eq!(EvalResult::Expr(Expr::list(&[Expr::keyword("foo")])), r);
+    }
+
+    #[test]
+    fn keyword_predicate_works() {
+        let r = run_interpreter("(keyword? :foo)");
+        assert_eq!(EvalResult::Expr(Expr::symbol("True")), r);
+
+        let r = run_interpreter("(keyword? foo)");
+        assert_eq!(EvalResult::Expr(Expr::symbol("False")), r);
+    }
+
+    #[test]
+    fn fraction_arithmetic_is_exact() {
+        let r = run_interpreter("(+ 1/3 1/6)");
+        assert_eq!(EvalResult::Expr(Rc::new(Expr::Ratio(1, 2))), r);
+
+        let r = run_interpreter("(* 2/3 3/4)");
+        assert_eq!(EvalResult::Expr(Rc::new(Expr::Ratio(1, 2))), r);
+
+        let r = run_interpreter("(- 1/2 3)");
+        assert_eq!(EvalResult::Expr(Rc::new(Expr::Ratio(-5, 2))), r);
+
+        let r = run_interpreter("(+ 1/2 1/2)");
+        assert_eq!(EvalResult::Expr(Expr::int(1)), r);
+    }
+
+    #[test]
+    fn fraction_of_extreme_integers_does_not_overflow() {
+        let r = run_interpreter("-9223372036854775808/-9223372036854775808");
+        assert_eq!(EvalResult::Expr(Expr::int(1)), r);
+
+        let r = run_interpreter("(/ -4611686018427387904 -9223372036854775808 1/2)");
+        assert_eq!(EvalResult::Expr(Expr::int(1)), r);
+    }
+
+    #[test]
+    fn fraction_mixed_with_float_gives_float() {
+        match run_interpreter("(+ 1/2 0.25)") {
+            EvalResult::Expr(expr) => match &*expr {
+                Expr::FNum(n) => assert!((n - 0.75).abs() <= 1e-8),
+                e => panic!("Expected a float, got {:?}", e),
+            },
+            r => panic!("Expected EvalResult::Expr, got {:?}", r),
+        }
+
+        for (program, expected) in [("(+ 1/2 2.0)", 2.5), ("(* 1/3 3.0)", 1.0)].iter() {
+            match run_interpreter(program) {
+                EvalResult::Expr(expr) => match &*expr {
+                    Expr::FNum(n) => assert!((n - expected).abs() <= 1e-8),
+                    e => panic!("Expected a float for {}, got {:?}", program, e),
+                },
+                r => panic!("Expected EvalResult::Expr for {}, got {:?}", program, r),
+            }
+        }
+
+        match run_interpreter("(/ 1/2 0)") {
+            EvalResult::Err(error) => assert_eq!("Division by zero!", error),
+            r => panic!("Expected EvalResult::Err, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn with_timeout_returns_value_in_time() {
+        let r = run_interpreter("(with-timeout 10000 (+ 1 2))");
+        assert_eq!(EvalResult::Expr(Expr::int(3)), r);
+    }
+
+    #[test]
+    fn with_timeout_aborts_and_leaves_environment_usable() {
+        let mut env = Environment::default();
+        let mut run = |program: &str| eval(parse(&lex(program).unwrap()).unwrap(), &mut env);
+
+        run("(fn countdown (n) (if (= n 0) 0 (countdown (- n 1))))");
+        assert_eq!(
+            EvalResult::Expr(Expr::int(0)),
+            run("(with-timeout 10000 (countdown 10))")
+        );
+        match run("(with-timeout 0 (countdown 1000))") {
+            EvalResult::Err(error) => assert_eq!("Evaluation timed out!", error),
+            r => panic!("Expected EvalResult::Err, got {:?}", r),
+        }
+        assert_eq!(EvalResult::Expr(Expr::int(5)), run("(+ 2 3)"));
+    }
+
+    #[test]
+    fn diff_reports_differing_values() {
+        let r = run_interpreter("(diff (1 2 3) (1 9 3))");
+        let expected = Expr::list(&[Expr::list(&[
+            Expr::symbol("value"),
+            Expr::list(&[Expr::int(1)]),
+            Expr::int(2),
+            Expr::int(9),
+        ])]);
+        assert_eq!(EvalResult::Expr(expected), r);
+
+        let r = run_interpreter("(diff (1 (2 3)) (1 (2 4)))");
+        let expected = Expr::list(&[Expr::list(&[
+            Expr::symbol("value"),
+            Expr::list(&[Expr::int(1), Expr::int(1)]),
+            Expr::int(3),
+            Expr::int(4),
+        ])]);
+        assert_eq!(EvalResult::Expr(expected), r);
+    }
+
+    #[test]
+    fn diff_reports_length_mismatch() {
+        let r = run_interpreter("(diff (1) (1 2))");
+        let expected = Expr::list(&[Expr::list(&[
+            Expr::symbol("length"),
+            Expr::list(&[]),
+            Expr::int(1),
+            Expr::int(2),
+        ])]);
+        assert_eq!(EvalResult::Expr(expected), r);
+    }
+
+    #[test]
+    fn diff_of_identical_structures_is_empty() {
+        let r = run_interpreter("(diff (1 (2 3)) (1 (2 3)))");
+        assert_eq!(EvalResult::Expr(Expr::list(&[])), r);
+    }
+
+    #[test]
+    fn spy_passes_value_through() {
+        let r = run_interpreter("(+ 1 (spy mid (* 2 3)))");
+        assert_eq!(EvalResult::Expr(Expr::int(7)), r);
+    }
+
+    #[test]
+    fn min_and_max_of_list_work() {
+        let r = run_interpreter("(max-list (3 1 4 1 5))");
+        assert_eq!(EvalResult::Expr(Expr::int(5)), r);
+
+        let r = run_interpreter("(min-list (3 1 4))");
+        assert_eq!(EvalResult::Expr(Expr::int(1)), r);
+
+        match run_interpreter("(max-list False)") {
+            EvalResult::Err(error) => assert_eq!("Cannot get max-list of an empty list!", error),
+            r => panic!("Expected EvalResult::Err, got {:?}", r),
+        }
+
+        match run_interpreter("(min-list (3 a 4))") {
+            EvalResult::Err(error) => {
+                assert_eq!("min-list must be performed on a list of numbers!", error)
+            }
+            r => panic!("Expected EvalResult::Err, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn bindings_persist_across_programs_in_one_environment() {
+        let mut env = Environment::default();
+        assert_eq!(
+            EvalResult::Unit,
+            run_interpreter_with_env("(fn double (x) (* x 2))", &mut env)
+        );
+        assert_eq!(
+            EvalResult::Unit,
+            run_interpreter_with_env("(let y 20)", &mut env)
+        );
+        assert_eq!(
+            EvalResult::Expr(Expr::int(42)),
+            run_interpreter_with_env("(double (+ y 1))", &mut env)
+        );
+    }
+
+    #[test]
+    fn every_top_level_expression_is_evaluated() {
+        let program = "(fn double (x) (* x 2))
+        (let y 20)
+        (double (+ y 1))";
+        assert_eq!(EvalResult::Expr(Expr::int(42)), run_interpreter(program));
+    }
+
+    #[test]
+    fn first_failing_top_level_expression_stops_evaluation() {
+        match run_interpreter("(let x 1) (let + 2) (let y 3)") {
+            EvalResult::Err(error) => assert_eq!("Reserved variable or function name!", error),
+            r => panic!("Expected EvalResult::Err, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn zero_is_truthy_by_default() {
+        let mut env = Environment::default();
+        assert_eq!(
+            EvalResult::Expr(Expr::int(1)),
+            run_interpreter_with_env("(if 0 1 2)", &mut env)
+        );
+    }
+
+    #[test]
+    fn zero_is_falsy_when_enabled() {
+        let mut env = Environment {
+            zero_is_falsy: true,
+            ..Environment::default()
+        };
+        assert_eq!(
+            EvalResult::Expr(Expr::int(2)),
+            run_interpreter_with_env("(if 0 1 2)", &mut env)
+        );
+        assert_eq!(
+            EvalResult::Expr(Expr::symbol("False")),
+            run_interpreter_with_env("(and 1 0)", &mut env)
+        );
+        assert_eq!(
+            EvalResult::Expr(Expr::int(1)),
+            run_interpreter_with_env("(if 5 1 2)", &mut env)
+        );
+    }
+
+    #[test]
+    fn string_evaluates_to_itself() {
+        let r = run_interpreter(r#"(let s "hello world") s"#);
+        assert_eq!(EvalResult::Expr(Expr::string("hello world")), r);
+    }
+
+    #[test]
+    fn comparison_chains_work() {
+        let cases = [
+            ("(< 1 2 3)", "True"),
+            ("(< 1 3 2)", "False"),
+            ("(> 3 2 1)", "True"),
+            ("(> 3 3)", "False"),
+            ("(<= 1 1 2)", "True"),
+            ("(>= 3 3 4)", "False"),
+            ("(< 1/3 0.5)", "True"),
+        ];
+        for (program, expected) in cases.iter() {
+            assert_eq!(
+                EvalResult::Expr(Expr::symbol(expected)),
+                run_interpreter(program),
+                "{}",
+                program
+            );
+        }
+    }
+
+    #[test]
+    fn equality_of_exact_numbers_is_exact() {
+        let cases = [
+            ("(= 1/1000000000 0)", "False"),
+            ("(= 9007199254740993 9007199254740992)", "False"),
+            ("(= 1/2 2/4)", "True"),
+            ("(= 1/2 0.5)", "True"),
+            ("(= 2 2.0)", "True"),
+        ];
+        for (program, expected) in cases.iter() {
+            assert_eq!(
+                EvalResult::Expr(Expr::symbol(expected)),
+                run_interpreter(program),
+                "{}",
+                program
+            );
+        }
+    }
+
+    #[test]
+    fn comparison_of_non_numbers_fails() {
+        match run_interpreter("(< 1 a)") {
+            EvalResult::Err(error) => {
+                assert_eq!("Comparisons must be performed on numbers!", error)
+            }
+            r => panic!("Expected EvalResult::Err, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn rotate_works() {
+        let r = run_interpreter("(rotate 1 (1 2 3))");
+        let expected = Expr::list(&[Expr::int(2), Expr::int(3), Expr::int(1)]);
+        assert_eq!(EvalResult::Expr(expected), r);
+
+        let r = run_interpreter("(rotate -1 (1 2 3))");
+        let expected = Expr::list(&[Expr::int(3), Expr::int(1), Expr::int(2)]);
+        assert_eq!(EvalResult::Expr(expected), r);
+
+        let r = run_interpreter("(rotate 4 (1 2 3))");
+        let expected = Expr::list(&[Expr::int(2), Expr::int(3), Expr::int(1)]);
+        assert_eq!(EvalResult::Expr(expected), r);
+
+        let r = run_interpreter("(rotate 2 False)");
+        assert_eq!(EvalResult::Expr(Expr::list(&[])), r);
+    }
+
+    #[test]
+    fn shuffle_with_fixed_seed_is_reproducible() {
+        let program = "(seed-rng 42) (shuffle (1 2 3 4 5 6 7 8))";
+        let first = run_interpreter(program);
+        assert_eq!(first, run_interpreter(program));
+
+        match first {
+            EvalResult::Expr(expr) => match &*expr {
+                Expr::List(xs) => {
+                    let mut xs = xs.iter().filter_map(|x| x.as_f64()).collect::<Vec<f64>>();
+                    xs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                    assert_eq!(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0], xs);
+                }
+                e => panic!("Expected a list, got {:?}", e),
+            },
+            r => panic!("Expected EvalResult::Expr, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn unary_math_works() {
+        let cases = [
+            ("(add1 4)", 5),
+            ("(sub1 4)", 3),
+            ("(square 5)", 25),
+            ("(cube 3)", 27),
+            ("(cube -2)", -8),
+        ];
+        for (program, expected) in cases.iter() {
+            assert_eq!(
+                EvalResult::Expr(Expr::int(*expected)),
+                run_interpreter(program)
+            );
+        }
+        assert_eq!(
+            EvalResult::Expr(Rc::new(Expr::Ratio(1, 4))),
+            run_interpreter("(square 1/2)")
+        );
+    }
+
+    #[test]
+    fn unary_math_with_bad_arguments_fails() {
+        let cases = [
+            ("(add1 1 2)", "Invalid add1! Must be '(add1 x)'!"),
+            ("(cube)", "Invalid cube! Must be '(cube x)'!"),
+            (
+                "(square a)",
+                "Mathematical operations must be performed on numbers!",
+            ),
+        ];
+        for (program, message) in cases.iter() {
+            match run_interpreter(program) {
+                EvalResult::Err(error) => assert_eq!(*message, error),
+                r => panic!("Expected EvalResult::Err, got {:?}", r),
+            }
+        }
+    }
+
+    #[test]
+    fn remainder_works() {
+        assert_eq!(EvalResult::Expr(Expr::int(2)), run_interpreter("(% 17 5)"));
+        assert_eq!(
+            EvalResult::Expr(Expr::int(3)),
+            run_interpreter("(% 17 10 4)")
+        );
+        assert_eq!(
+            EvalResult::Expr(Expr::int(-2)),
+            run_interpreter("(% -17 5)")
+        );
+        assert_eq!(
+            EvalResult::Expr(Expr::fnum(0.5)),
+            run_interpreter("(% 5/2 1)")
+        );
+
+        for program in &["(%)", "(% 5)"] {
+            match run_interpreter(program) {
+                EvalResult::Err(error) => assert_eq!(
+                    "Remainder must be performed on at least two numbers!",
+                    error
+                ),
+                r => panic!("Expected EvalResult::Err, got {:?}", r),
+            }
+        }
+    }
+
+    #[test]
+    fn commented_program_evaluates() {
+        let program = "; Sum two variables
+        (let x 1) ; the first
+        ;; (let x 100)
+        (let y 2)
+        (+ x y) ; the result";
+        assert_eq!(EvalResult::Expr(Expr::int(3)), run_interpreter(program));
+    }
+
+    #[test]
+    fn input_completeness_ignores_parens_in_strings() {
+        assert!(is_complete(r#"(print "(not a paren")"#));
+        assert!(is_complete("(+ 1 2) ; (unclosed comment"));
+        assert!(!is_complete("(let x (+ 1"));
+        assert!(!is_complete(r#"(print ")"#));
+        assert!(is_complete("(let x\n  (+ 1 2))"));
+    }
+
+    #[test]
+    fn input_with_stray_closing_paren_is_complete() {
+        assert!(is_complete("(+ 1 2))"));
+        assert!(is_complete(") (+ 1"));
+        assert!(is_complete("(+ 1 2)) (let x"));
+        assert!(!is_complete("(fn f (x)"));
+        assert!(!is_complete("(fn f (x)\n  (* x 2)"));
+        assert!(is_complete("(fn f (x)\n  (* x 2))"));
+    }
+
+    #[test]
+    fn depth_command_reports_contexts_and_names() {
+        let mut env = Environment::default();
+        run_interpreter_with_env("(fn double (x) (* x 2)) (let y 20)", &mut env);
+        assert_eq!(
+            Some("Contexts: 1\nDefined: False True double y".to_string()),
+            run_meta_command(":depth", &mut env)
+        );
+        assert_eq!(None, run_meta_command("(+ 1 2)", &mut env));
+    }
+
+    #[test]
+    fn cond_picks_first_true_clause() {
+        let program = |x: &str| {
+            format!(
+                "(fn sign-of (x) (cond ((< x 0) -1) ((= x 0) 0) (True 1))) (sign-of {})",
+                x
+            )
+        };
+        assert_eq!(
+            EvalResult::Expr(Expr::int(-1)),
+            run_interpreter(&program("-5"))
+        );
+        assert_eq!(
+            EvalResult::Expr(Expr::int(0)),
+            run_interpreter(&program("0"))
+        );
+        assert_eq!(
+            EvalResult::Expr(Expr::int(1)),
+            run_interpreter(&program("5"))
+        );
+    }
+
+    #[test]
+    fn cond_without_matching_clause_is_unit() {
+        assert_eq!(
+            EvalResult::Unit,
+            run_interpreter("(cond (False 1) ((= 1 2) 2))")
+        );
+
+        match run_interpreter("(cond (True))") {
+            EvalResult::Err(error) => {
+                assert_eq!("Invalid cond clause! Must be '(predicate body)'!", error)
+            }
+            r => panic!("Expected EvalResult::Err, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn begin_returns_last_value() {
+        assert_eq!(
+            EvalResult::Expr(Expr::int(42)),
+            run_interpreter("(begin (print 1) 42)")
+        );
+        assert_eq!(EvalResult::Unit, run_interpreter("(begin)"));
+
+        let program = "(fn f (x) (begin (let y (* x 2)) (print y) (+ y 1))) (f 5)";
+        assert_eq!(EvalResult::Expr(Expr::int(11)), run_interpreter(program));
+    }
+
+    #[test]
+    fn typed_let_checks_value() {
+        assert_eq!(
+            EvalResult::Expr(Expr::int(5)),
+            run_interpreter("(let x :number 5) x")
+        );
+        assert_eq!(
+            EvalResult::Expr(Expr::string("hi")),
+            run_interpreter(r#"(let s :string "hi") s"#)
+        );
+        assert_eq!(
+            EvalResult::Expr(Expr::int(5)),
+            run_interpreter("(let x 5) x")
+        );
+
+        let cases = [
+            (
+                r#"(let x :number "hi")"#,
+                "Type mismatch! x must be a number but got a string!",
+            ),
+            (
+                "(let x :integer 5)",
+                "Invalid typed variable definition! Must be 'let x :type expr' with a type of number, symbol, list, string, keyword!",
+            ),
+        ];
+        for (program, message) in cases.iter() {
+            match run_interpreter(program) {
+                EvalResult::Err(error) => assert_eq!(*message, error),
+                r => panic!("Expected EvalResult::Err, got {:?}", r),
+            }
+        }
+    }
+
+    #[test]
+    fn type_names_are_not_reserved() {
+        let program =
+            r#"(fn f (string) (concat string "!")) (let number 2) (list (f "hi") number)"#;
+        let expected = Expr::list(&[Expr::string("hi!"), Expr::int(2)]);
+        assert_eq!(EvalResult::Expr(expected), run_interpreter(program));
+    }
+
+    #[test]
+    fn untyped_pair_pattern_still_destructures() {
+        let r = run_interpreter("(let (a b) (list 1 2)) (list b a)");
+        let expected = Expr::list(&[Expr::int(2), Expr::int(1)]);
+        assert_eq!(EvalResult::Expr(expected), r);
+
+        let r = run_interpreter("(let (x number) (list 1 2)) (list number x)");
+        let expected = Expr::list(&[Expr::int(2), Expr::int(1)]);
+        assert_eq!(EvalResult::Expr(expected), r);
+    }
+
+    #[test]
+    fn while_loop_counts_down() {
+        let program = "(let n 10000) (let total 0)
+        (while (> n 0) (begin (let total (+ total n)) (let n (- n 1))))
+        (list n total)";
+        let expected = Expr::list(&[Expr::int(0), Expr::int(50005000)]);
+        assert_eq!(EvalResult::Expr(expected), run_interpreter(program));
+    }
+
+    #[test]
+    fn while_loop_with_false_predicate_never_runs() {
+        let program = "(let n 0) (while False (let n 1)) n";
+        assert_eq!(EvalResult::Expr(Expr::int(0)), run_interpreter(program));
+    }
+
+    #[test]
+    fn type_command_reports_type_of_value() {
+        let mut env = Environment::default();
+        run_interpreter_with_env("(fn double (x) (* x 2)) (let y 2/3)", &mut env);
+        let cases = [
+            (":type 5", "number"),
+            (":type (list 1 2)", "list"),
+            (":type (double y)", "number"),
+            (r#":type "hi""#, "string"),
+            (":type :key", "keyword"),
+            (":type +", "function"),
+            (":type double", "function"),
+            (":type (print 1)", "unit"),
+        ];
+        for (command, expected) in cases.iter() {
+            assert_eq!(
+                Some(expected.to_string()),
+                run_meta_command(command, &mut env),
+                "{}",
+                command
+            );
+        }
+    }
+
+    #[test]
+    fn set_updates_outer_variable_from_function() {
+        let program = "(let count 0)
+        (fn bump (n) (set! count (+ count n)))
+        (bump 2)
+        (bump 3)
+        count";
+        assert_eq!(EvalResult::Expr(Expr::int(5)), run_interpreter(program));
+    }
+
+    #[test]
+    fn set_of_unbound_or_constant_fails() {
+        let cases = [
+            ("(set! x 1)", "Cannot set unbound variable x!"),
+            (
+                "(defconst PI 3) (set! PI 4)",
+                "Cannot redefine constant PI!",
             ),
+        ];
+        for (program, message) in cases.iter() {
+            match run_interpreter(program) {
+                EvalResult::Err(error) => assert_eq!(*message, error),
+                r => panic!("Expected EvalResult::Err, got {:?}", r),
+            }
         }
     }
+
+    #[test]
+    fn while_loop_with_set_terminates() {
+        let program = "(let n 5) (let steps 0)
+        (while (> n 0) (begin (set! n (- n 1)) (set! steps (add1 steps))))
+        (list n steps)";
+        let expected = Expr::list(&[Expr::int(0), Expr::int(5)]);
+        assert_eq!(EvalResult::Expr(expected), run_interpreter(program));
+    }
+
+    #[test]
+    fn break_leaves_while_loop() {
+        let program = "(let n 0)
+        (while True (begin (if (= n 3) (break)) (set! n (add1 n))))
+        n";
+        assert_eq!(EvalResult::Expr(Expr::int(3)), run_interpreter(program));
+    }
+
+    #[test]
+    fn continue_skips_to_next_iteration() {
+        let program = "(let n 0) (let total 0)
+        (while (< n 6) (begin (set! n (add1 n)) (if (= (% n 2) 0) (continue)) (set! total (+ total n))))
+        total";
+        assert_eq!(EvalResult::Expr(Expr::int(9)), run_interpreter(program));
+    }
+
+    #[test]
+    fn break_and_continue_outside_loop_fail() {
+        let cases = [
+            ("(break)", "Cannot use break outside of a loop!"),
+            ("(continue)", "Cannot use continue outside of a loop!"),
+            (
+                "(fn stop (x) (break)) (while True (stop 1))",
+                "Cannot use break outside of a loop!\nCall stack: stop",
+            ),
+        ];
+        for (program, message) in cases.iter() {
+            match run_interpreter(program) {
+                EvalResult::Err(error) => assert_eq!(*message, error),
+                r => panic!("Expected EvalResult::Err, got {:?}", r),
+            }
+        }
+    }
+
+    #[test]
+    fn return_leaves_function_early() {
+        let guard = "(fn f (x) (begin (if (< x 0) (return 0)) (let y (* x 2)) (+ y 1)))";
+        let cases = [("(f -5)", 0), ("(f 5)", 11)];
+        for (call, expected) in cases.iter() {
+            let program = format!("{} {}", guard, call);
+            assert_eq!(
+                EvalResult::Expr(Expr::int(*expected)),
+                run_interpreter(&program),
+                "{}",
+                call
+            );
+        }
+
+        let nested =
+            "(fn g (x) (begin (if (> x 0) (begin (if (> x 10) (return :big)) :small) :none)))
+        (list (g 20) (g 5) (g 0))";
+        let expected = Expr::list(&[
+            Expr::keyword("big"),
+            Expr::keyword("small"),
+            Expr::keyword("none"),
+        ]);
+        assert_eq!(EvalResult::Expr(expected), run_interpreter(nested));
+    }
+
+    #[test]
+    fn return_only_leaves_innermost_function() {
+        let program = "(fn inner (x) (begin (return (* x 2)) 0))
+        (fn outer (x) (+ (inner x) 1))
+        (outer 5)";
+        assert_eq!(EvalResult::Expr(Expr::int(11)), run_interpreter(program));
+    }
+
+    #[test]
+    fn return_outside_function_fails() {
+        let mut env = Environment::default();
+        match run_interpreter_with_env("(return 1)", &mut env) {
+            EvalResult::Err(error) => {
+                assert_eq!("Cannot use return outside of a function!", error)
+            }
+            r => panic!("Expected EvalResult::Err, got {:?}", r),
+        }
+        assert_eq!(
+            EvalResult::Expr(Expr::int(2)),
+            run_interpreter_with_env("(fn f (x) x) (f 2)", &mut env)
+        );
+    }
+
+    #[test]
+    fn lambda_can_be_applied_inline() {
+        let r = run_interpreter("((lambda (x) (* x x)) 5)");
+        assert_eq!(EvalResult::Expr(Expr::int(25)), r);
+
+        let r = run_interpreter("((lambda (x y) (+ x y)) 1 (+ 1 1))");
+        assert_eq!(EvalResult::Expr(Expr::int(3)), r);
+    }
+
+    #[test]
+    fn lambda_can_be_stored_in_a_variable() {
+        let r = run_interpreter("(let sq (lambda (x) (* x x))) (sq 6)");
+        assert_eq!(EvalResult::Expr(Expr::int(36)), r);
+    }
+
+    #[test]
+    fn lambda_does_not_leak_its_parameters() {
+        let r = run_interpreter("(let x 1) ((lambda (x) x) 2) x");
+        assert_eq!(EvalResult::Expr(Expr::int(1)), r);
+    }
+
+    #[test]
+    fn lambda_with_wrong_arity_fails() {
+        match run_interpreter("((lambda (x y) x) 1)") {
+            EvalResult::Err(_) => (),
+            r => panic!("Expected EvalResult::Err, got {:?}", r),
+        }
+    }
+
+    fn counting_env(calls: &Rc<Cell<usize>>) -> Environment {
+        let mut env = Environment::default();
+        let counter = calls.clone();
+        let _ = env.register_native(
+            "tick",
+            Box::new(move |args| {
+                counter.set(counter.get() + 1);
+                EvalResult::Expr(args[0].clone())
+            }),
+        );
+        env
+    }
+
+    #[test]
+    fn defmemo_caches_results() {
+        let fib = "(if (< (tick n) 2) n (+ (fib (- n 1)) (fib (- n 2))))";
+
+        let plain_calls = Rc::new(Cell::new(0));
+        let program = format!("(fn fib (n) {}) (fib 20)", fib);
+        let plain = run_interpreter_with_env(&program, &mut counting_env(&plain_calls));
+        assert_eq!(EvalResult::Expr(Expr::int(6765)), plain);
+
+        let memo_calls = Rc::new(Cell::new(0));
+        let program = format!("(defmemo fib (n) {}) (fib 20)", fib);
+        let memo = run_interpreter_with_env(&program, &mut counting_env(&memo_calls));
+        assert_eq!(plain, memo);
+        assert_eq!(21, memo_calls.get());
+        assert!(plain_calls.get() > 20000);
+
+        let calls = Rc::new(Cell::new(0));
+        let program = format!("(defmemo fib (n) {}) (fib 30)", fib);
+        let r = run_interpreter_with_env(&program, &mut counting_env(&calls));
+        assert_eq!(EvalResult::Expr(Expr::int(832040)), r);
+        assert_eq!(31, calls.get());
+    }
+
+    #[test]
+    fn defmemo_keys_on_argument_values() {
+        let calls = Rc::new(Cell::new(0));
+        let mut env = counting_env(&calls);
+        let program = "(defmemo f (x) (tick x))
+        (f 1) (f 1.0) (f 2/2) (f (list 1 \"a\")) (f (list 1.0 \"a\")) (f (list 1 (quote a)))";
+        run_interpreter_with_env(program, &mut env);
+        assert_eq!(3, calls.get());
+    }
+
+    #[test]
+    fn redefining_memoized_function_drops_cache() {
+        let program = "(defmemo f (n) (* n 2)) (f 3) (fn f (n) (* n 3)) (f 3)";
+        let r = run_interpreter(program);
+        assert_eq!(EvalResult::Expr(Expr::int(9)), r);
+    }
+
+    #[test]
+    fn cross_type_equality_records_warning() {
+        let mut env = Environment::default();
+        let r = run_interpreter_with_env("(= 1 \"a\")", &mut env);
+        assert_eq!(EvalResult::Expr(Expr::symbol("False")), r);
+        assert_eq!(
+            vec!["Warning: comparing number with string!".to_string()],
+            env.take_warnings()
+        );
+        assert!(env.take_warnings().is_empty());
+
+        run_interpreter_with_env("(= 1 1)", &mut env);
+        assert!(env.take_warnings().is_empty());
+    }
+
+    #[test]
+    fn warnings_builtin_lists_collected_warnings() {
+        let r = run_interpreter("(= :a 1) (warnings)");
+        let expected = Expr::list(&[Expr::string("Warning: comparing keyword with number!")]);
+        assert_eq!(EvalResult::Expr(expected), r);
+    }
+
+    #[test]
+    fn shadowing_warns_for_let_but_not_for_parameters() {
+        let mut env = Environment {
+            warn_shadowing: true,
+            ..Default::default()
+        };
+        run_interpreter_with_env("(let x 1) (fn f (x) x) (f 2)", &mut env);
+        assert!(env.take_warnings().is_empty());
+
+        run_interpreter_with_env("(fn g (y) (let x y)) (g 3)", &mut env);
+        assert_eq!(
+            vec!["Warning: x shadows a binding in an outer context!".to_string()],
+            env.take_warnings()
+        );
+    }
+
+    #[test]
+    fn syntax_errors_include_location() {
+        match run_interpreter("(let x 1)\n(print x))") {
+            EvalResult::Err(error) => assert!(
+                error.starts_with("Parse error: line 2, col 10:"),
+                "Got {}",
+                error
+            ),
+            r => panic!("Expected EvalResult::Err, got {:?}", r),
+        }
+
+        match run_interpreter("(print\n  \"oops)") {
+            EvalResult::Err(error) => assert!(
+                error.starts_with("Lex error: line 2, col 3:"),
+                "Got {}",
+                error
+            ),
+            r => panic!("Expected EvalResult::Err, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn integer_arithmetic_stays_integer() {
+        let cases = [
+            ("(/ 7 2)", 3),
+            ("(/ -7 2)", -3),
+            ("(+ 1 1)", 2),
+            ("(* 6 7)", 42),
+            ("(% 17 5)", 2),
+            ("(add1 4)", 5),
+        ];
+        for (program, expected) in cases.iter() {
+            match run_interpreter(program) {
+                EvalResult::Expr(expr) => assert_eq!(Expr::Int(*expected), *expr),
+                r => panic!("Expected EvalResult::Expr for {}, got {:?}", program, r),
+            }
+        }
+    }
+
+    #[test]
+    fn float_operand_promotes_to_float() {
+        for program in ["(/ 7.0 2)", "(+ 1 2.5 0)", "(* 2 1.75)"].iter() {
+            match run_interpreter(program) {
+                EvalResult::Expr(expr) => match &*expr {
+                    Expr::FNum(n) => assert!((n - 3.5).abs() <= 1e-8),
+                    e => panic!("Expected a float for {}, got {:?}", program, e),
+                },
+                r => panic!("Expected EvalResult::Expr for {}, got {:?}", program, r),
+            }
+        }
+    }
+
+    #[test]
+    fn division_by_zero_fails() {
+        for program in [
+            "(/ 5 0)",
+            "(/ 1.5 0)",
+            "(/ 6 3 0.0)",
+            "(% 5 0)",
+            "(/ 1/2 0)",
+        ]
+        .iter()
+        {
+            match run_interpreter(program) {
+                EvalResult::Err(error) => assert_eq!("Division by zero!", error),
+                r => panic!("Expected EvalResult::Err for {}, got {:?}", program, r),
+            }
+        }
+
+        assert_eq!(EvalResult::Expr(Expr::int(2)), run_interpreter("(/ 6 3)"));
+        assert_eq!(EvalResult::Expr(Expr::int(0)), run_interpreter("(* 5 0)"));
+        assert_eq!(EvalResult::Expr(Expr::int(0)), run_interpreter("(/ 0 5)"));
+    }
+
+    #[test]
+    fn and_or_short_circuit() {
+        let r = run_interpreter("(let hit 0) (and False (begin (set! hit 1) True)) hit");
+        assert_eq!(EvalResult::Expr(Expr::int(0)), r);
+
+        let r = run_interpreter("(let hit 0) (or True (begin (set! hit 1) True)) hit");
+        assert_eq!(EvalResult::Expr(Expr::int(0)), r);
+
+        let r = run_interpreter("(or True (/ 1 0))");
+        assert_eq!(EvalResult::Expr(Expr::symbol("True")), r);
+
+        let r = run_interpreter("(and False (/ 1 0))");
+        assert_eq!(EvalResult::Expr(Expr::symbol("False")), r);
+    }
+
+    #[test]
+    fn and_or_evaluate_until_decided() {
+        let r = run_interpreter("(let hit 0) (or False (begin (set! hit 1) False)) hit");
+        assert_eq!(EvalResult::Expr(Expr::int(1)), r);
+
+        let r = run_interpreter("(and True (/ 1 0))");
+        assert_eq!(EvalResult::Err("Division by zero!".into()), r);
+
+        let r = run_interpreter("(and True True)");
+        assert_eq!(EvalResult::Expr(Expr::symbol("True")), r);
+    }
+
+    #[test]
+    fn select_evaluates_chosen_branch() {
+        let r = run_interpreter("(select (= 2 2) yes no)");
+        assert_eq!(EvalResult::Expr(Expr::symbol("yes")), r);
+
+        let r = run_interpreter("(select (= 2 1) yes no)");
+        assert_eq!(EvalResult::Expr(Expr::symbol("no")), r);
+    }
+
+    #[test]
+    fn select_skips_unchosen_branch() {
+        let program = "((let x 1) (select False (let x 2) x) x)";
+        let r = run_interpreter(program);
+        let expected = Expr::list(&[Expr::int(1), Expr::int(1)]);
+        assert_eq!(EvalResult::Expr(expected), r);
+    }
+
+    #[test]
+    fn identical_and_equal_differ() {
+        let program = "((let a (1 2))
+        (identical? a a)
+        (identical? a (1 2))
+        (equal? a (1 2)))";
+        let expected = Expr::list(&[
+            Expr::symbol("True"),
+            Expr::symbol("False"),
+            Expr::symbol("True"),
+        ]);
+        assert_eq!(EvalResult::Expr(expected), run_interpreter(program));
+    }
+
+    #[test]
+    fn overloaded_function_dispatches_on_arity() {
+        let program = "((fn area ((r) (* 3 r r)) ((w h) (* w h)))
+        (area 2)
+        (area 2 5))";
+        let expected = Expr::list(&[Expr::int(12), Expr::int(10)]);
+        assert_eq!(EvalResult::Expr(expected), run_interpreter(program));
+    }
+
+    #[test]
+    fn overloaded_function_without_matching_arity_fails() {
+        let program = "((fn area ((r) (* 3 r r)) ((w h) (* w h)))
+        (area 1 2 3))";
+        match run_interpreter(program) {
+            EvalResult::Err(error) => {
+                assert_eq!("No definition of area takes 3 arguments!", error)
+            }
+            r => panic!("Expected EvalResult::Err, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn builtin_names_are_reserved() {
+        for name in &["select", "butlast", "identical?"] {
+            let program = format!("(let {} 1)", name);
+            match run_interpreter(&program) {
+                EvalResult::Err(error) => {
+                    assert_eq!("Reserved variable or function name!", error)
+                }
+                r => panic!("Expected EvalResult::Err, got {:?}", r),
+            }
+        }
+    }
+
+    #[test]
+    fn constant_can_be_read() {
+        let program = "((defconst RATE 2.5) (* RATE 2))";
+        let expected = Expr::list(&[Expr::fnum(5.0)]);
+        assert_eq!(EvalResult::Expr(expected), run_interpreter(program));
+    }
+
+    #[test]
+    fn constant_cannot_be_redefined() {
+        for program in &[
+            "((defconst PI 3.14159) (defconst PI 3))",
+            "((defconst PI 3.14159) (let PI 3))",
+            "((defconst PI 3.14159) (fn PI (x) x))",
+        ] {
+            match run_interpreter(program) {
+                EvalResult::Err(error) => assert_eq!("Cannot redefine constant PI!", error),
+                r => panic!("Expected EvalResult::Err, got {:?}", r),
+            }
+        }
+    }
+
+    #[test]
+    fn constant_can_be_shadowed_by_parameter() {
+        let program = "((defconst x 1) (fn f (x) (+ x 1)) (f 5) x)";
+        let expected = Expr::list(&[Expr::int(6), Expr::int(1)]);
+        assert_eq!(EvalResult::Expr(expected), run_interpreter(program));
+    }
 }
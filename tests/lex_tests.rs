@@ -1,11 +1,11 @@
 #[cfg(test)]
 mod lex_tests {
-    use rust_lisp::lex::{lex, Token};
+    use rust_lisp::lex::{lex, lex_with_spans, LexError, Span, Token};
 
     #[test]
     fn can_lex_lpar() {
         lex("(").map_or_else(
-            |err| assert!(false, format!("{:?}", err)),
+            |err| panic!("{:?}", err),
             |tokens| {
                 assert_eq!(1, tokens.len());
                 assert_eq!(Token::LPar, tokens[0]);
@@ -16,7 +16,7 @@ mod lex_tests {
     #[test]
     fn can_lex_rpar() {
         lex(")").map_or_else(
-            |err| assert!(false, format!("{:?}", err)),
+            |err| panic!("{:?}", err),
             |tokens| {
                 assert_eq!(1, tokens.len());
                 assert_eq!(Token::RPar, tokens[0]);
@@ -27,7 +27,7 @@ mod lex_tests {
     #[test]
     fn can_lex_literal() {
         lex("hello-world").map_or_else(
-            |err| assert!(false, format!("{:?}", err)),
+            |err| panic!("{:?}", err),
             |tokens| {
                 assert_eq!(1, tokens.len());
                 assert_eq!(Token::Literal("hello-world".into()), tokens[0]);
@@ -48,7 +48,7 @@ mod lex_tests {
 
         match lex(input) {
             Ok(actual) => assert_eq!(output, actual),
-            _ => assert!(false),
+            _ => panic!(),
         }
     }
 
@@ -70,15 +70,107 @@ mod lex_tests {
 
         match lex(input) {
             Ok(actual) => assert_eq!(output, actual),
-            _ => assert!(false),
+            _ => panic!(),
         }
     }
 
     #[test]
     fn lex_empty_string() {
         lex("").map_or_else(
-            |err| assert!(false, format!("{:?}", err)),
+            |err| panic!("{:?}", err),
             |tokens| assert_eq!(0, tokens.len()),
         );
     }
+
+    #[test]
+    fn can_lex_string() {
+        lex(r#"(print "hello world" "say \"hi\"")"#).map_or_else(
+            |err| panic!("{:?}", err),
+            |tokens| {
+                assert_eq!(
+                    vec![
+                        Token::LPar,
+                        Token::Literal("print".into()),
+                        Token::Str("hello world".into()),
+                        Token::Str("say \"hi\"".into()),
+                        Token::RPar,
+                    ],
+                    tokens
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn unterminated_string_fails() {
+        match lex(r#"(print "hello)"#) {
+            Err(LexError::UnterminatedString) => {}
+            r => panic!("Expected LexError::UnterminatedString, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn tokens_have_line_and_column() {
+        let (tokens, spans) = lex_with_spans("(let x\n  \"a b\") ; c\nx").unwrap();
+        assert_eq!(tokens.len(), spans.len());
+        let at = |line, col| Span { line, col };
+        assert_eq!(
+            vec![at(1, 1), at(1, 2), at(1, 6), at(2, 3), at(2, 8), at(3, 1)],
+            spans
+        );
+        assert_eq!("line 2, col 3", spans[3].to_string());
+    }
+
+    #[test]
+    fn unterminated_string_reports_its_start() {
+        match lex_with_spans("(print 1)\n(print \"hello)") {
+            Err((LexError::UnterminatedString, span)) => {
+                assert_eq!(Span { line: 2, col: 8 }, span)
+            }
+            r => panic!("Expected LexError::UnterminatedString, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn comments_are_skipped() {
+        lex("(let x 1) ; set x\n;; whole line\nx;trailing\n\"a ; b\"").map_or_else(
+            |err| panic!("{:?}", err),
+            |tokens| {
+                assert_eq!(
+                    vec![
+                        Token::LPar,
+                        Token::Literal("let".into()),
+                        Token::Literal("x".into()),
+                        Token::Literal("1".into()),
+                        Token::RPar,
+                        Token::Literal("x".into()),
+                        Token::Str("a ; b".into()),
+                    ],
+                    tokens
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn quasiquote_shorthand_is_lexed() {
+        lex("`(a ,b ,@cs)").map_or_else(
+            |err| panic!("{:?}", err),
+            |tokens| {
+                assert_eq!(
+                    vec![
+                        Token::Quasiquote,
+                        Token::LPar,
+                        Token::Literal("a".into()),
+                        Token::Unquote,
+                        Token::Literal("b".into()),
+                        Token::UnquoteSplicing,
+                        Token::Literal("cs".into()),
+                        Token::RPar,
+                    ],
+                    tokens
+                );
+            },
+        );
+    }
 }
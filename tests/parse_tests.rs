@@ -1,25 +1,58 @@
 #[cfg(test)]
 mod parse_tests {
-    use rust_lisp::lex::Token;
-    use rust_lisp::parse::parse;
+    use rust_lisp::lex::{lex, lex_with_spans, Span, Token};
+    use rust_lisp::parse::{parse, parse_program, parse_program_with_spans, ParseError};
+    use rust_lisp::risp;
     use rust_lisp::types::Expr;
 
     #[test]
     fn parse_fnum() {
         parse(&[Token::Literal("1".into())]).map_or_else(
-            |err| assert!(false, format!("{:?}", err)),
-            |expr| assert_eq!(Expr::fnum(1.0), expr),
+            |err| panic!("{:?}", err),
+            |expr| assert_eq!(Expr::int(1), expr),
         );
     }
 
     #[test]
     fn parse_symbol() {
         parse(&[Token::Literal("hello".into())]).map_or_else(
-            |err| assert!(false, format!("{:?}", err)),
+            |err| panic!("{:?}", err),
             |expr| assert_eq!(Expr::symbol("hello"), expr),
         )
     }
 
+    #[test]
+    fn parse_keyword() {
+        parse(&[Token::Literal(":foo".into())]).map_or_else(
+            |err| panic!("{:?}", err),
+            |expr| assert_eq!(Expr::keyword("foo"), expr),
+        );
+        parse(&[Token::Literal(":".into())]).map_or_else(
+            |err| panic!("{:?}", err),
+            |expr| assert_eq!(Expr::symbol(":"), expr),
+        );
+    }
+
+    #[test]
+    fn parse_ratio() {
+        parse(&[Token::Literal("2/6".into())]).map_or_else(
+            |err| panic!("{:?}", err),
+            |expr| assert_eq!(Expr::Ratio(1, 3), *expr),
+        );
+        parse(&[Token::Literal("1/0".into())]).map_or_else(
+            |err| panic!("{:?}", err),
+            |expr| assert_eq!(Expr::symbol("1/0"), expr),
+        );
+    }
+
+    #[test]
+    fn parse_string() {
+        parse(&[Token::Str("1 2".into())]).map_or_else(
+            |err| panic!("{:?}", err),
+            |expr| assert_eq!(Expr::string("1 2"), expr),
+        );
+    }
+
     #[test]
     fn parse_list() {
         let tokens = [
@@ -31,10 +64,7 @@ mod parse_tests {
         ];
         let expected = Expr::list(&[Expr::symbol("+"), Expr::fnum(1.8), Expr::fnum(1.2)]);
 
-        parse(&tokens).map_or_else(
-            |err| assert!(false, format!("{:?}", err)),
-            |expr| assert_eq!(expected, expr),
-        );
+        parse(&tokens).map_or_else(|err| panic!("{:?}", err), |expr| assert_eq!(expected, expr));
     }
 
     #[test]
@@ -49,10 +79,7 @@ mod parse_tests {
 
         let expected = Expr::list(&[Expr::list(&[Expr::symbol("A")])]);
 
-        parse(&tokens).map_or_else(
-            |err| assert!(false, format!("{:?}", err)),
-            |expr| assert_eq!(expected, expr),
-        );
+        parse(&tokens).map_or_else(|err| panic!("{:?}", err), |expr| assert_eq!(expected, expr));
     }
 
     #[test]
@@ -79,9 +106,148 @@ mod parse_tests {
             Expr::list(&[Expr::symbol("*"), Expr::fnum(1.2), Expr::fnum(-12.8)]),
         ]);
 
+        parse(&tokens).map_or_else(|err| panic!("{:?}", err), |expr| assert_eq!(expected, expr));
+    }
+
+    #[test]
+    fn parse_program_reads_every_expression() {
+        let tokens = [
+            Token::LPar,
+            Token::Literal("let".into()),
+            Token::Literal("x".into()),
+            Token::Literal("1".into()),
+            Token::RPar,
+            Token::Literal("x".into()),
+        ];
+        let expected = vec![
+            Expr::list(&[Expr::symbol("let"), Expr::symbol("x"), Expr::int(1)]),
+            Expr::symbol("x"),
+        ];
+
+        parse_program(&tokens).map_or_else(
+            |err| panic!("{:?}", err),
+            |exprs| assert_eq!(expected, exprs),
+        );
+        parse_program(&[])
+            .map_or_else(|err| panic!("{:?}", err), |exprs| assert!(exprs.is_empty()));
+    }
+
+    #[test]
+    fn risp_macro_matches_parse() {
+        let tokens = lex("(+ 1 (* 2 3))").unwrap();
         parse(&tokens).map_or_else(
-            |err| assert!(false, format!("{:?}", err)),
-            |expr| assert_eq!(expected, expr),
+            |err| panic!("{:?}", err),
+            |expr| assert_eq!(risp!((+ 1 (* 2 3))), expr),
         );
     }
+
+    #[test]
+    fn parse_signed_numbers() {
+        for (literal, n) in &[
+            ("-5", Expr::int(-5)),
+            ("-3.25", Expr::fnum(-3.25)),
+            ("+3.2", Expr::fnum(3.2)),
+            ("-0.5", Expr::fnum(-0.5)),
+        ] {
+            parse(&[Token::Literal(literal.to_string())])
+                .map_or_else(|err| panic!("{:?}", err), |expr| assert_eq!(*n, expr));
+        }
+    }
+
+    #[test]
+    fn parse_minus_operator_and_negative_operands() {
+        let tokens = lex("(- -1 -2)").unwrap();
+        let expected = Expr::list(&[Expr::symbol("-"), Expr::int(-1), Expr::int(-2)]);
+        parse(&tokens).map_or_else(|err| panic!("{:?}", err), |expr| assert_eq!(expected, expr));
+    }
+
+    #[test]
+    fn parse_errors_report_the_offending_token() {
+        let (tokens, spans) = lex_with_spans("(+ 1 2)\n  (fn f (x)\n (* x x)").unwrap();
+        match parse_program_with_spans(&tokens, &spans) {
+            Err((ParseError::BadParse(message), Some(span))) => {
+                assert_eq!("Unclosed delimiter!", message);
+                assert_eq!(Span { line: 2, col: 3 }, span);
+            }
+            r => panic!("Expected ParseError::BadParse, got {:?}", r),
+        }
+
+        let (tokens, spans) = lex_with_spans("(+ 1 2))").unwrap();
+        match parse_program_with_spans(&tokens, &spans) {
+            Err((ParseError::BadParse(_), Some(span))) => {
+                assert_eq!(Span { line: 1, col: 8 }, span)
+            }
+            r => panic!("Expected ParseError::BadParse, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn parse_int_and_float_literals() {
+        let tokens = lex("(3 -4 3.0 2.5)").unwrap();
+        parse(&tokens).map_or_else(
+            |err| panic!("{:?}", err),
+            |expr| match &*expr {
+                Expr::List(xs) => {
+                    assert!(matches!(*xs[0], Expr::Int(3)));
+                    assert!(matches!(*xs[1], Expr::Int(-4)));
+                    assert!(matches!(*xs[2], Expr::FNum(_)));
+                    assert!(matches!(*xs[3], Expr::FNum(_)));
+                }
+                e => panic!("Expected a list, got {:?}", e),
+            },
+        );
+    }
+
+    #[test]
+    fn parse_quasiquote_shorthand() {
+        let tokens = lex("`(a ,b ,@cs)").unwrap();
+        let expected = Expr::list(&[
+            Expr::symbol("quasiquote"),
+            Expr::list(&[
+                Expr::symbol("a"),
+                Expr::list(&[Expr::symbol("unquote"), Expr::symbol("b")]),
+                Expr::list(&[Expr::symbol("unquote-splicing"), Expr::symbol("cs")]),
+            ]),
+        ]);
+        parse(&tokens).map_or_else(|err| panic!("{:?}", err), |expr| assert_eq!(expected, expr));
+    }
+
+    #[test]
+    fn parse_nested_quasiquote_shorthand() {
+        let tokens = lex("`(1 `(2 ,(3 ,x)) ,,y)").unwrap();
+        let wrap = |form, expr| Expr::list(&[Expr::symbol(form), expr]);
+        let expected = wrap(
+            "quasiquote",
+            Expr::list(&[
+                Expr::int(1),
+                wrap(
+                    "quasiquote",
+                    Expr::list(&[
+                        Expr::int(2),
+                        wrap(
+                            "unquote",
+                            Expr::list(&[Expr::int(3), wrap("unquote", Expr::symbol("x"))]),
+                        ),
+                    ]),
+                ),
+                wrap("unquote", wrap("unquote", Expr::symbol("y"))),
+            ]),
+        );
+        parse(&tokens).map_or_else(|err| panic!("{:?}", err), |expr| assert_eq!(expected, expr));
+    }
+
+    #[test]
+    fn parse_shorthand_without_expression_fails() {
+        for program in ["`", "(a ,@"].iter() {
+            let tokens = lex(program).unwrap();
+            match parse(&tokens) {
+                Err(ParseError::BadParse(message)) => assert!(
+                    message.starts_with("Missing expression after"),
+                    "Got {}",
+                    message
+                ),
+                r => panic!("Expected ParseError::BadParse, got {:?}", r),
+            }
+        }
+    }
 }